@@ -0,0 +1,106 @@
+//! Async sniffing for tokio `AsyncRead` sources (requires the `async` feature).
+
+use std::path::Path;
+
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::error::{Result, SnifferError};
+use crate::metadata::Metadata;
+use crate::sniffer::{READ_CHUNK_SIZE, Sniffer};
+
+impl Sniffer {
+    /// Sniff a CSV file at the given path without blocking the runtime.
+    pub async fn sniff_async_path<P: AsRef<Path>>(&mut self, path: P) -> Result<Metadata> {
+        let file = tokio::fs::File::open(path.as_ref()).await?;
+        self.sniff_async_reader(tokio::io::BufReader::new(file))
+            .await
+    }
+
+    /// Sniff CSV data from an async reader.
+    ///
+    /// Only as many bytes as the sample size requires are read (plus one to
+    /// check for the end of input). Dialect scoring is CPU-bound and runs on
+    /// tokio's blocking pool, so this must be called from within a tokio runtime.
+    pub async fn sniff_async_reader<R: AsyncRead + Unpin>(
+        &mut self,
+        mut reader: R,
+    ) -> Result<Metadata> {
+        let mut state = self.sniff_state();
+        let mut chunk = vec![0u8; READ_CHUNK_SIZE];
+        let reached_eof = loop {
+            let want = state.next_read_len(READ_CHUNK_SIZE);
+            let bytes_read = reader.read(&mut chunk[..want]).await?;
+            if let Some(reached_eof) = state.feed_read(&chunk[..bytes_read]) {
+                break reached_eof;
+            }
+        };
+
+        tokio::task::spawn_blocking(move || state.finish_with_eof(reached_eof))
+            .await
+            .map_err(|e| SnifferError::Io(std::io::Error::other(e)))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample::SampleSize;
+
+    /// Run a future on a current-thread runtime. The `rt` feature enabled by
+    /// `async` provides it, so tokio is not a dev-dependency.
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn test_sniff_async_reader() {
+        block_on(async {
+            let data: &[u8] = b"id|name\n1|Alice\n2|Bob\n3|Carol\n";
+            let mut sniffer = Sniffer::new();
+            let metadata = sniffer.sniff_async_reader(data).await.unwrap();
+
+            assert_eq!(metadata.dialect.delimiter, b'|');
+            assert_eq!(metadata.fields, vec!["id", "name"]);
+            assert_eq!(metadata.types, sniffer.sniff_bytes(data).unwrap().types);
+        });
+    }
+
+    #[test]
+    fn test_sniff_async_reader_stops_at_sample() {
+        block_on(async {
+            let data = b"a,b\n1,2\n3,4\n5,6\n7,8\n";
+            let mut reader = &data[..];
+            let mut sniffer = Sniffer::new();
+            sniffer.sample_size(SampleSize::Bytes(8));
+            sniffer.sniff_async_reader(&mut reader).await.unwrap();
+
+            // 8 sample bytes plus the one-byte probe
+            assert_eq!(reader.len(), data.len() - 9);
+        });
+    }
+
+    #[test]
+    fn test_sniff_async_path() {
+        block_on(async {
+            use std::io::Write;
+
+            let mut file = tempfile::NamedTempFile::new().unwrap();
+            file.write_all(b"x;y\n1.5;2\n2.5;3\n").unwrap();
+            let metadata = Sniffer::new().sniff_async_path(file.path()).await.unwrap();
+
+            assert_eq!(metadata.dialect.delimiter, b';');
+            assert_eq!(metadata.num_fields, 2);
+        });
+    }
+
+    #[test]
+    fn test_sniff_async_empty() {
+        block_on(async {
+            let result = Sniffer::new().sniff_async_reader(&b""[..]).await;
+            assert!(matches!(result, Err(SnifferError::EmptyData)));
+        });
+    }
+}
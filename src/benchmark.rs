@@ -3,7 +3,7 @@
 //! This module provides tools to validate the Table Uniformity Method implementation
 //! against the same test datasets used by CSVsniffer, enabling accuracy comparison.
 
-use csv_nose::{Metadata, Quote, Sniffer};
+use csv_nose::{LineTerminator, Metadata, Quote, ScoringConfig, Sniffer};
 use foldhash::{HashMap, HashMapExt};
 use std::fs;
 use std::io::{self, BufRead};
@@ -11,8 +11,8 @@ use std::path::{Path, PathBuf};
 
 /// Expected dialect from annotation file.
 ///
-/// Note: `encoding`, `escape_char`, and `line_terminator` are parsed from the annotation file
-/// but not yet used in validation. They are retained for potential future accuracy comparisons.
+/// Note: `encoding` is parsed from the annotation file but not yet used in validation.
+/// It is retained for potential future accuracy comparisons.
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
 pub struct ExpectedDialect {
@@ -24,14 +24,6 @@ pub struct ExpectedDialect {
     pub line_terminator: LineTerminator,
 }
 
-/// Line terminator type.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum LineTerminator {
-    Lf,
-    Cr,
-    CrLf,
-}
-
 /// Result of benchmarking a single file.
 #[derive(Debug, Clone)]
 pub struct FileResult {
@@ -43,6 +35,16 @@ pub struct FileResult {
     pub detected_delimiter: u8,
     pub expected_quote: Option<u8>,
     pub detected_quote: Option<u8>,
+    /// Whether backslash escaping was detected exactly when annotated.
+    /// Does not affect `passed`.
+    pub escape_match: bool,
+    pub expected_escape: Option<u8>,
+    pub detected_escape: Option<u8>,
+    /// Whether the detected line terminator matches the annotation.
+    /// Does not affect `passed`.
+    pub line_terminator_match: bool,
+    pub expected_line_terminator: LineTerminator,
+    pub detected_line_terminator: Option<LineTerminator>,
     pub error: Option<String>,
 }
 
@@ -55,6 +57,8 @@ pub struct BenchmarkResult {
     pub errors: usize,
     pub delimiter_matches: usize,
     pub quote_matches: usize,
+    pub escape_matches: usize,
+    pub line_terminator_matches: usize,
     pub file_results: Vec<FileResult>,
 }
 
@@ -106,6 +110,26 @@ impl BenchmarkResult {
         }
     }
 
+    /// Calculate escape accuracy.
+    pub fn escape_accuracy(&self) -> f64 {
+        let valid = self.total - self.errors;
+        if valid == 0 {
+            0.0
+        } else {
+            self.escape_matches as f64 / valid as f64
+        }
+    }
+
+    /// Calculate line terminator accuracy.
+    pub fn line_terminator_accuracy(&self) -> f64 {
+        let valid = self.total - self.errors;
+        if valid == 0 {
+            0.0
+        } else {
+            self.line_terminator_matches as f64 / valid as f64
+        }
+    }
+
     /// Calculate precision (true positives / (true positives + false positives)).
     /// For dialect detection, this is essentially the success ratio.
     pub fn precision(&self) -> f64 {
@@ -163,6 +187,24 @@ impl BenchmarkResult {
                 }
             }
 
+            if result.error.is_none() && !result.escape_match {
+                print!(
+                    " - escape: expected {} got {}",
+                    escape_label(result.expected_escape),
+                    escape_label(result.detected_escape)
+                );
+            }
+
+            if result.error.is_none() && !result.line_terminator_match {
+                print!(
+                    " - line terminator: expected {} got {}",
+                    result.expected_line_terminator,
+                    result
+                        .detected_line_terminator
+                        .map_or("none", |t| t.as_str())
+                );
+            }
+
             if let Some(ref err) = result.error {
                 print!(" - {err}");
             }
@@ -196,6 +238,11 @@ impl BenchmarkResult {
             self.delimiter_accuracy() * 100.0
         );
         println!("Quote accuracy:     {:.1}%", self.quote_accuracy() * 100.0);
+        println!("Escape accuracy:    {:.1}%", self.escape_accuracy() * 100.0);
+        println!(
+            "Line term accuracy: {:.1}%",
+            self.line_terminator_accuracy() * 100.0
+        );
         println!();
         println!("Precision:          {:.3}", self.precision());
         println!("Recall:             {:.3}", self.recall());
@@ -203,6 +250,11 @@ impl BenchmarkResult {
     }
 }
 
+/// Format an escape character for benchmark output.
+fn escape_label(escape: Option<u8>) -> String {
+    escape.map_or_else(|| "none".to_string(), |c| format!("'{}'", c as char))
+}
+
 /// Parse an annotation file and return a map of file name to expected dialect.
 pub fn parse_annotations(path: &Path) -> io::Result<HashMap<String, ExpectedDialect>> {
     let file = fs::File::open(path)?;
@@ -233,7 +285,7 @@ pub fn parse_annotations(path: &Path) -> io::Result<HashMap<String, ExpectedDial
         let encoding = parts[1].to_string();
         let delimiter = parse_delimiter(parts[2]);
         let quote_char = parse_quote(parts[3]);
-        let _escape_char = parse_escape(parts[4]);
+        let escape_char = parse_escape(parts[4]);
         let line_terminator = parse_line_terminator(parts[5]);
 
         annotations.insert(
@@ -243,7 +295,7 @@ pub fn parse_annotations(path: &Path) -> io::Result<HashMap<String, ExpectedDial
                 encoding,
                 delimiter,
                 quote_char,
-                escape_char: _escape_char,
+                escape_char,
                 line_terminator,
             },
         );
@@ -292,15 +344,19 @@ fn parse_escape(name: &str) -> Option<u8> {
 /// Parse line terminator name.
 fn parse_line_terminator(name: &str) -> LineTerminator {
     match name.to_lowercase().as_str() {
-        "lf" => LineTerminator::Lf,
-        "cr" => LineTerminator::Cr,
-        "crlf" => LineTerminator::CrLf,
-        _ => LineTerminator::Lf,
+        "lf" => LineTerminator::LF,
+        "cr" => LineTerminator::CR,
+        "crlf" => LineTerminator::CRLF,
+        _ => LineTerminator::LF,
     }
 }
 
 /// Run benchmark on a directory of CSV files.
-pub fn run_benchmark(data_dir: &Path, annotations_path: &Path) -> io::Result<BenchmarkResult> {
+pub fn run_benchmark(
+    data_dir: &Path,
+    annotations_path: &Path,
+    scoring: &ScoringConfig,
+) -> io::Result<BenchmarkResult> {
     let annotations = parse_annotations(annotations_path)?;
     let mut result = BenchmarkResult::default();
 
@@ -309,7 +365,14 @@ pub fn run_benchmark(data_dir: &Path, annotations_path: &Path) -> io::Result<Ben
         let file_path = data_dir.join(file_name);
         result.total += 1;
 
-        let file_result = benchmark_file(&file_path, expected);
+        let file_result = benchmark_file(&file_path, expected, scoring);
+
+        if file_result.error.is_none() && file_result.escape_match {
+            result.escape_matches += 1;
+        }
+        if file_result.error.is_none() && file_result.line_terminator_match {
+            result.line_terminator_matches += 1;
+        }
 
         if file_result.error.is_some() {
             result.errors += 1;
@@ -339,7 +402,11 @@ pub fn run_benchmark(data_dir: &Path, annotations_path: &Path) -> io::Result<Ben
 }
 
 /// Benchmark a single file against expected dialect.
-fn benchmark_file(file_path: &Path, expected: &ExpectedDialect) -> FileResult {
+fn benchmark_file(
+    file_path: &Path,
+    expected: &ExpectedDialect,
+    scoring: &ScoringConfig,
+) -> FileResult {
     let file_name = expected.file_name.clone();
 
     // Check if file exists
@@ -353,12 +420,19 @@ fn benchmark_file(file_path: &Path, expected: &ExpectedDialect) -> FileResult {
             detected_delimiter: 0,
             expected_quote: expected.quote_char,
             detected_quote: None,
+            escape_match: false,
+            expected_escape: expected.escape_char,
+            detected_escape: None,
+            line_terminator_match: false,
+            expected_line_terminator: expected.line_terminator,
+            detected_line_terminator: None,
             error: Some("File not found".to_string()),
         };
     }
 
     // Run sniffer
     let mut sniffer = Sniffer::new();
+    sniffer.scoring(scoring.clone());
     let metadata: Result<Metadata, _> = sniffer.sniff_path(file_path);
 
     match metadata {
@@ -369,8 +443,16 @@ fn benchmark_file(file_path: &Path, expected: &ExpectedDialect) -> FileResult {
                 Quote::Some(c) => Some(c),
             };
 
+            let detected_escape = detected_escape_char(&meta);
+
             let delimiter_match = detected_delimiter == expected.delimiter;
             let quote_match = detected_quote == expected.quote_char;
+            // Annotations leave the escape empty when no embedded quotes occur and
+            // name the quote character when quotes are doubled; both mean "not
+            // backslash", so only the backslash/non-backslash distinction is scored.
+            let escape_match =
+                (expected.escape_char == Some(b'\\')) == (detected_escape == Some(b'\\'));
+            let line_terminator_match = meta.dialect.line_terminator == expected.line_terminator;
             let passed = delimiter_match && quote_match;
 
             FileResult {
@@ -382,6 +464,12 @@ fn benchmark_file(file_path: &Path, expected: &ExpectedDialect) -> FileResult {
                 detected_delimiter,
                 expected_quote: expected.quote_char,
                 detected_quote,
+                escape_match,
+                expected_escape: expected.escape_char,
+                detected_escape,
+                line_terminator_match,
+                expected_line_terminator: expected.line_terminator,
+                detected_line_terminator: Some(meta.dialect.line_terminator),
                 error: None,
             }
         }
@@ -394,11 +482,27 @@ fn benchmark_file(file_path: &Path, expected: &ExpectedDialect) -> FileResult {
             detected_delimiter: 0,
             expected_quote: expected.quote_char,
             detected_quote: None,
+            escape_match: false,
+            expected_escape: expected.escape_char,
+            detected_escape: None,
+            line_terminator_match: false,
+            expected_line_terminator: expected.line_terminator,
+            detected_line_terminator: None,
             error: Some(e.to_string()),
         },
     }
 }
 
+/// The escape character in annotation terms: the backslash, the quote character
+/// itself for doubled quotes, or `None` when the file is unquoted.
+fn detected_escape_char(meta: &Metadata) -> Option<u8> {
+    match (meta.dialect.escape, meta.dialect.quote) {
+        (Some(escape), _) => Some(escape),
+        (None, Quote::Some(q)) if meta.dialect.double_quote => Some(q),
+        _ => None,
+    }
+}
+
 /// Find the annotation file for a data directory.
 pub fn find_annotations(data_dir: &Path) -> Option<PathBuf> {
     // Check for annotations in parent directory
@@ -449,11 +553,18 @@ mod tests {
         assert_eq!(parse_quote("none"), None);
     }
 
+    #[test]
+    fn test_parse_escape() {
+        assert_eq!(parse_escape("backslash"), Some(b'\\'));
+        assert_eq!(parse_escape("doublequote"), Some(b'"'));
+        assert_eq!(parse_escape(""), None);
+    }
+
     #[test]
     fn test_parse_line_terminator() {
-        assert_eq!(parse_line_terminator("lf"), LineTerminator::Lf);
-        assert_eq!(parse_line_terminator("cr"), LineTerminator::Cr);
-        assert_eq!(parse_line_terminator("crlf"), LineTerminator::CrLf);
+        assert_eq!(parse_line_terminator("lf"), LineTerminator::LF);
+        assert_eq!(parse_line_terminator("cr"), LineTerminator::CR);
+        assert_eq!(parse_line_terminator("crlf"), LineTerminator::CRLF);
     }
 
     #[test]
@@ -465,6 +576,8 @@ mod tests {
             errors: 5,
             delimiter_matches: 85,
             quote_matches: 90,
+            escape_matches: 95,
+            line_terminator_matches: 76,
             file_results: vec![],
         };
 
@@ -473,6 +586,8 @@ mod tests {
         assert!((result.error_ratio() - 0.05).abs() < 0.001);
         assert!((result.delimiter_accuracy() - 0.894736).abs() < 0.001); // 85/95
         assert!((result.quote_accuracy() - 0.947368).abs() < 0.001); // 90/95
+        assert!((result.escape_accuracy() - 1.0).abs() < 0.001); // 95/95
+        assert!((result.line_terminator_accuracy() - 0.8).abs() < 0.001); // 76/95
         assert!((result.f1_score() - 0.80).abs() < 0.001);
     }
 }
@@ -0,0 +1,280 @@
+//! Calibration of scoring weights against annotated test datasets.
+//!
+//! Coordinate ascent over the weights and penalties of `ScoringConfig`: each
+//! parameter in turn is scaled up and down by a relative step, and a change
+//! is kept when it raises the number of files whose delimiter and quote
+//! character match their annotation. The step is halved after a round
+//! without improvement. Penalties are capped at 1.0 so that they never turn
+//! into bonuses.
+//!
+//! The parameters are tuned and measured on the same files, so the reported
+//! accuracy is training accuracy; check a tuned configuration against a
+//! dataset it was not calibrated on before relying on it.
+
+use csv_nose::{Quote, ScoringConfig, Sniffer};
+use rayon::prelude::*;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::benchmark::{ExpectedDialect, parse_annotations};
+
+/// Initial relative step for each parameter.
+const INITIAL_STEP: f64 = 0.2;
+
+/// Smallest relative step tried before the search stops early.
+const MIN_STEP: f64 = 0.01;
+
+/// An annotated file held in memory for repeated sniffing.
+struct Sample {
+    data: Vec<u8>,
+    expected: ExpectedDialect,
+}
+
+/// Outcome of a calibration run.
+#[derive(Debug, Clone)]
+pub struct Calibration {
+    /// The tuned configuration.
+    pub config: ScoringConfig,
+    /// Number of annotated files found on disk.
+    pub total: usize,
+    /// Files detected correctly with the starting configuration.
+    pub baseline_passed: usize,
+    /// Files detected correctly with the tuned configuration.
+    pub passed: usize,
+}
+
+impl Calibration {
+    /// Accuracy of the starting configuration.
+    pub fn baseline_accuracy(&self) -> f64 {
+        ratio(self.baseline_passed, self.total)
+    }
+
+    /// Accuracy of the tuned configuration on the files it was tuned on.
+    pub fn accuracy(&self) -> f64 {
+        ratio(self.passed, self.total)
+    }
+
+    /// The tuned configuration as a loadable file, with a summary header.
+    pub fn to_config_file(&self) -> String {
+        format!(
+            "# csv-nose scoring configuration\n\
+             # Calibrated on {} files: {:.1}% correct (starting configuration: {:.1}%)\n\
+             # Training accuracy, measured on the calibration files (no held-out split)\n\
+             {}",
+            self.total,
+            self.accuracy() * 100.0,
+            self.baseline_accuracy() * 100.0,
+            self.config
+        )
+    }
+}
+
+fn ratio(passed: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        passed as f64 / total as f64
+    }
+}
+
+/// Whether `key` is a weight or penalty searched by calibration (bonus
+/// thresholds, priorities and the tie-break band are left as they are).
+fn is_tunable(key: &str) -> bool {
+    !(key.ends_with("_rows")
+        || key.ends_with("_fields")
+        || key.contains("priority")
+        || key == "tie_break_ratio"
+        || key == "custom_delimiter_penalty")
+}
+
+/// A candidate value for `key`, with penalties capped at 1.0 (no penalty).
+fn tuned_value(key: &str, value: f64) -> f64 {
+    if key.contains("penalty") {
+        value.min(1.0)
+    } else {
+        value
+    }
+}
+
+/// Tune `start` on the files of each `(data_dir, annotations_path)` dataset
+/// for at most `rounds` rounds of coordinate ascent. Progress is reported on
+/// stderr.
+pub fn run_calibration(
+    datasets: &[(&Path, &Path)],
+    start: ScoringConfig,
+    rounds: usize,
+) -> io::Result<Calibration> {
+    let samples = load_samples(datasets)?;
+    if samples.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "no annotated files found in the data directories",
+        ));
+    }
+
+    let baseline_passed = count_passed(&samples, &start);
+    eprintln!(
+        "Baseline: {baseline_passed}/{} files correct",
+        samples.len()
+    );
+
+    let keys: Vec<String> = start
+        .parameters()
+        .into_iter()
+        .map(|(key, _)| key)
+        .filter(|key| is_tunable(key))
+        .collect();
+
+    let mut best = start;
+    let mut best_passed = baseline_passed;
+    let mut step = INITIAL_STEP;
+    for round in 1..=rounds {
+        let mut improved = false;
+        for key in &keys {
+            let current = parameter(&best, key);
+            for factor in [1.0 + step, 1.0 - step] {
+                let value = tuned_value(key, current * factor);
+                if value == current {
+                    continue;
+                }
+                let mut candidate = best.clone();
+                candidate
+                    .set_parameter(key, value)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
+                let passed = count_passed(&samples, &candidate);
+                if passed > best_passed {
+                    eprintln!(
+                        "  {key}: {current} -> {value} ({passed}/{} correct)",
+                        samples.len()
+                    );
+                    best = candidate;
+                    best_passed = passed;
+                    improved = true;
+                    break;
+                }
+            }
+        }
+        eprintln!(
+            "Round {round}: {best_passed}/{} files correct (step {:.0}%)",
+            samples.len(),
+            step * 100.0
+        );
+        if !improved {
+            step /= 2.0;
+            if step < MIN_STEP {
+                break;
+            }
+        }
+    }
+
+    Ok(Calibration {
+        config: best,
+        total: samples.len(),
+        baseline_passed,
+        passed: best_passed,
+    })
+}
+
+/// The current value of a parameter key.
+fn parameter(config: &ScoringConfig, key: &str) -> f64 {
+    config
+        .parameters()
+        .into_iter()
+        .find(|(k, _)| k == key)
+        .map_or(0.0, |(_, value)| value)
+}
+
+/// Read every annotated file that exists in its data directory.
+fn load_samples(datasets: &[(&Path, &Path)]) -> io::Result<Vec<Sample>> {
+    let mut samples = Vec::new();
+    for &(data_dir, annotations_path) in datasets {
+        let mut annotations: Vec<ExpectedDialect> =
+            parse_annotations(annotations_path)?.into_values().collect();
+        annotations.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+        for expected in annotations {
+            let Ok(data) = fs::read(data_dir.join(&expected.file_name)) else {
+                continue;
+            };
+            samples.push(Sample { data, expected });
+        }
+    }
+    Ok(samples)
+}
+
+/// Number of samples whose sniffed delimiter and quote character match their
+/// annotation under `config` (the benchmark's pass criterion).
+fn count_passed(samples: &[Sample], config: &ScoringConfig) -> usize {
+    samples
+        .par_iter()
+        .filter(|sample| {
+            let mut sniffer = Sniffer::new();
+            sniffer.scoring(config.clone());
+            sniffer.sniff_reader(&sample.data[..]).is_ok_and(|meta| {
+                let quote = match meta.dialect.quote {
+                    Quote::None => None,
+                    Quote::Some(c) => Some(c),
+                };
+                meta.dialect.delimiter == sample.expected.delimiter
+                    && quote == sample.expected.quote_char
+            })
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_tunable() {
+        assert!(is_tunable("type_weight"));
+        assert!(is_tunable("delimiter_penalty.space"));
+        assert!(!is_tunable("delimiter_priority.comma"));
+        assert!(!is_tunable("row_bonus_rows"));
+        assert!(!is_tunable("tie_break_ratio"));
+    }
+
+    #[test]
+    fn test_tuned_value_caps_penalties() {
+        assert_eq!(tuned_value("delimiter_penalty.comma", 1.2), 1.0);
+        assert_eq!(tuned_value("single_field_penalty", 0.6), 0.6);
+        assert_eq!(tuned_value("type_weight", 1.2), 1.2);
+    }
+
+    #[test]
+    fn test_run_calibration() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("a.csv"), "a;b;c\n1;2;3\n4;5;6\n7;8;9\n").unwrap();
+        fs::write(dir.path().join("b.csv"), "x|y\n\"p\"|q\nr|s\nt|u\n").unwrap();
+        let annotations = dir.path().join("annotations.txt");
+        fs::write(
+            &annotations,
+            "file_name|encoding|fields_delimiter|quotechar|escapechar|records_delimiter\n\
+             a.csv|utf-8|semicolon|doublequote||lf\n\
+             b.csv|utf-8|vslash|doublequote||lf\n\
+             missing.csv|utf-8|comma|doublequote||lf\n",
+        )
+        .unwrap();
+
+        let calibration = run_calibration(
+            &[(dir.path(), annotations.as_path())],
+            ScoringConfig::default(),
+            1,
+        )
+        .unwrap();
+        assert_eq!(calibration.total, 2);
+        assert!(calibration.passed >= calibration.baseline_passed);
+        assert!(
+            calibration
+                .config
+                .parameters()
+                .iter()
+                .filter(|(key, _)| key.contains("penalty"))
+                .all(|&(_, value)| value <= 1.0)
+        );
+
+        let loaded: ScoringConfig = calibration.to_config_file().parse().unwrap();
+        assert_eq!(loaded, calibration.config);
+    }
+}
@@ -1,8 +1,18 @@
 //! Encoding detection and transcoding using chardetng and `encoding_rs`.
 
+use std::borrow::Cow;
+
 use chardetng::EncodingDetector;
+use encoding_rs::{DecoderResult, Encoding};
 use simdutf8::basic::from_utf8;
 
+use crate::error::{Result, SnifferError};
+
+/// Maximum fraction of malformed sequences tolerated when decoding with a
+/// forced encoding. A small allowance covers a multi-byte character cut off at
+/// the end of a byte-limited sample.
+const MAX_MALFORMED_RATIO: f64 = 0.01;
+
 /// Check if the given bytes are valid UTF-8.
 ///
 /// Uses SIMD-accelerated validation for performance.
@@ -33,22 +43,46 @@ pub fn detect_encoding(data: &[u8]) -> EncodingInfo {
     EncodingInfo {
         is_utf8: valid_utf8,
         has_bom,
+        encoding: encoding_rs::UTF_8,
+        had_errors: !valid_utf8,
     }
 }
 
 /// Information about the detected encoding.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub struct EncodingInfo {
     /// Whether the data is valid UTF-8.
     pub is_utf8: bool,
-    /// Whether a UTF-8 BOM was present.
+    /// Whether a byte order mark (UTF-8 or UTF-16) was present.
     pub has_bom: bool,
+    /// The encoding the data was decoded from.
+    pub encoding: &'static Encoding,
+    /// Whether decoding hit malformed byte sequences (replaced with U+FFFD,
+    /// or passed through when the data was treated as UTF-8).
+    pub had_errors: bool,
 }
 
 impl EncodingInfo {
-    /// Create a new `EncodingInfo`.
+    /// Create a new `EncodingInfo` for UTF-8 data.
     pub const fn new(is_utf8: bool, has_bom: bool) -> Self {
-        Self { is_utf8, has_bom }
+        Self {
+            is_utf8,
+            has_bom,
+            encoding: encoding_rs::UTF_8,
+            had_errors: !is_utf8,
+        }
+    }
+
+    /// The WHATWG name of the encoding (e.g. `UTF-8`, `windows-1251`, `GBK`).
+    pub fn name(&self) -> &'static str {
+        self.encoding.name()
+    }
+}
+
+impl Default for EncodingInfo {
+    fn default() -> Self {
+        Self::new(true, false)
     }
 }
 
@@ -62,32 +96,28 @@ impl EncodingInfo {
 /// - UTF-16 LE/BE
 /// - And many more
 ///
-/// Returns (`transcoded_data`, `was_transcoded`). If `was_transcoded` is false,
-/// the original data is returned as-is (it was already valid UTF-8).
-pub fn detect_and_transcode(data: &[u8]) -> (std::borrow::Cow<'_, [u8]>, bool) {
+/// Returns the data as UTF-8 together with what was learned about the source
+/// encoding. Data that is already UTF-8 is returned as-is (borrowed, BOM
+/// included); `EncodingInfo::encoding` is then `UTF_8`.
+pub fn detect_and_transcode(data: &[u8]) -> (Cow<'_, [u8]>, EncodingInfo) {
     // Check for UTF-16 BOM first (chardetng doesn't handle these well)
     if data.len() >= 2 {
         // UTF-16 LE BOM: FF FE
         if data[0] == 0xFF && data[1] == 0xFE {
-            let (decoded, _, _) = encoding_rs::UTF_16LE.decode(data);
-            return (
-                std::borrow::Cow::Owned(decoded.into_owned().into_bytes()),
-                true,
-            );
+            return transcode(data, encoding_rs::UTF_16LE);
         }
         // UTF-16 BE BOM: FE FF
         if data[0] == 0xFE && data[1] == 0xFF {
-            let (decoded, _, _) = encoding_rs::UTF_16BE.decode(data);
-            return (
-                std::borrow::Cow::Owned(decoded.into_owned().into_bytes()),
-                true,
-            );
+            return transcode(data, encoding_rs::UTF_16BE);
         }
     }
 
     // Check if already valid UTF-8
     if is_utf8(data) {
-        return (std::borrow::Cow::Borrowed(data), false);
+        return (
+            Cow::Borrowed(data),
+            EncodingInfo::new(true, has_utf8_bom(data)),
+        );
     }
 
     // Use chardetng to detect encoding
@@ -97,15 +127,93 @@ pub fn detect_and_transcode(data: &[u8]) -> (std::borrow::Cow<'_, [u8]>, bool) {
 
     // If detected as UTF-8, return as-is (might have some invalid bytes)
     if encoding == encoding_rs::UTF_8 {
-        return (std::borrow::Cow::Borrowed(data), false);
+        return (
+            Cow::Borrowed(data),
+            EncodingInfo::new(false, has_utf8_bom(data)),
+        );
     }
 
     // Transcode to UTF-8
-    let (decoded, _, _) = encoding.decode(data);
-    (
-        std::borrow::Cow::Owned(decoded.into_owned().into_bytes()),
-        true,
-    )
+    transcode(data, encoding)
+}
+
+/// Decode `data` from `encoding` into UTF-8 bytes.
+///
+/// A BOM, if present, is honoured and stripped by `encoding_rs`.
+fn transcode(data: &[u8], encoding: &'static Encoding) -> (Cow<'static, [u8]>, EncodingInfo) {
+    let (decoded, used, had_errors) = encoding.decode(data);
+    let has_bom = Encoding::for_bom(data).is_some();
+    let info = EncodingInfo {
+        is_utf8: used == encoding_rs::UTF_8 && !had_errors,
+        has_bom,
+        encoding: used,
+        had_errors,
+    };
+    (Cow::Owned(decoded.into_owned().into_bytes()), info)
+}
+
+/// Decode `data` with a caller-chosen encoding instead of guessing.
+///
+/// A BOM is only stripped if it matches `encoding`. Returns
+/// `SnifferError::EncodingMismatch` when more than 1% of the decoded characters
+/// come from malformed sequences.
+pub fn transcode_with<'a>(
+    data: &'a [u8],
+    encoding: &'static Encoding,
+) -> Result<(Cow<'a, [u8]>, EncodingInfo)> {
+    let has_bom = Encoding::for_bom(data).is_some_and(|(bom_encoding, _)| bom_encoding == encoding);
+
+    let mut decoder = encoding.new_decoder_with_bom_removal();
+    let mut output = String::with_capacity(
+        decoder
+            .max_utf8_buffer_length(data.len())
+            .unwrap_or(data.len()),
+    );
+    let mut malformed = 0;
+    let mut input = data;
+    loop {
+        let (result, read) = decoder.decode_to_string_without_replacement(input, &mut output, true);
+        input = &input[read..];
+        match result {
+            DecoderResult::InputEmpty => break,
+            DecoderResult::OutputFull => {
+                output.reserve(
+                    decoder
+                        .max_utf8_buffer_length(input.len())
+                        .unwrap_or(input.len())
+                        .max(4),
+                );
+            }
+            DecoderResult::Malformed(_, _) => {
+                malformed += 1;
+                output.push('\u{FFFD}');
+            }
+        }
+    }
+
+    let chars = output.chars().count();
+    if malformed > 0 && malformed as f64 > chars as f64 * MAX_MALFORMED_RATIO {
+        return Err(SnifferError::EncodingMismatch {
+            encoding: encoding.name(),
+            malformed,
+            chars,
+        });
+    }
+
+    let info = EncodingInfo {
+        is_utf8: encoding == encoding_rs::UTF_8 && malformed == 0,
+        has_bom,
+        encoding,
+        had_errors: malformed > 0,
+    };
+    let data = if encoding == encoding_rs::UTF_8 && malformed == 0 {
+        // Keep the original bytes (and BOM) so the rest of the pipeline sees
+        // exactly what auto-detection would have passed through.
+        Cow::Borrowed(data)
+    } else {
+        Cow::Owned(output.into_bytes())
+    };
+    Ok((data, info))
 }
 
 #[cfg(test)]
@@ -154,8 +262,10 @@ mod tests {
     fn test_detect_and_transcode_utf8() {
         // Valid UTF-8 should not be transcoded
         let data = b"Hello, World!";
-        let (result, was_transcoded) = detect_and_transcode(data);
-        assert!(!was_transcoded);
+        let (result, info) = detect_and_transcode(data);
+        assert_eq!(info.encoding, encoding_rs::UTF_8);
+        assert!(info.is_utf8);
+        assert!(!info.had_errors);
         assert_eq!(&result[..], data);
     }
 
@@ -163,10 +273,36 @@ mod tests {
     fn test_detect_and_transcode_utf16_le() {
         // UTF-16 LE with BOM: "Hi"
         let data: &[u8] = &[0xFF, 0xFE, b'H', 0x00, b'i', 0x00];
-        let (result, was_transcoded) = detect_and_transcode(data);
-        assert!(was_transcoded);
+        let (result, info) = detect_and_transcode(data);
+        assert_eq!(info.encoding, encoding_rs::UTF_16LE);
+        assert!(info.has_bom);
         // Result should be UTF-8 (without BOM marker in content)
         assert!(is_utf8(&result));
+        assert_eq!(&result[..], b"Hi");
+    }
+
+    #[test]
+    fn test_transcode_with_forced_encoding() {
+        // "café" in Windows-1252
+        let data: &[u8] = b"caf\xe9,x\n";
+        let (result, info) = transcode_with(data, encoding_rs::WINDOWS_1252).unwrap();
+        assert_eq!(&result[..], "café,x\n".as_bytes());
+        assert_eq!(info.name(), "windows-1252");
+        assert!(!info.had_errors);
+    }
+
+    #[test]
+    fn test_transcode_with_mismatched_encoding() {
+        let data: &[u8] = b"caf\xe9,\xe8t\xe9\n";
+        let err = transcode_with(data, encoding_rs::UTF_8).unwrap_err();
+        assert!(matches!(
+            err,
+            SnifferError::EncodingMismatch {
+                encoding: "UTF-8",
+                malformed: 3,
+                ..
+            }
+        ));
     }
 
     #[test]
@@ -174,9 +310,11 @@ mod tests {
         // Windows-1251 encoded Cyrillic text: "Привет" (Hello in Russian)
         // П=0xCF, р=0xF0, и=0xE8, в=0xE2, е=0xE5, т=0xF2
         let data: &[u8] = &[0xCF, 0xF0, 0xE8, 0xE2, 0xE5, 0xF2];
-        let (result, was_transcoded) = detect_and_transcode(data);
+        let (result, info) = detect_and_transcode(data);
         // Should be transcoded since it's not valid UTF-8
-        assert!(was_transcoded);
+        assert_ne!(info.encoding, encoding_rs::UTF_8);
+        assert!(!info.is_utf8);
+        assert!(!info.has_bom);
         // Result should be valid UTF-8
         assert!(is_utf8(&result));
     }
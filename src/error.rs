@@ -1,8 +1,11 @@
 use std::io;
 use thiserror::Error;
 
+use crate::field_type::Type;
+
 /// Error type for CSV sniffing operations.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum SnifferError {
     /// IO error during file operations.
     #[error("IO error: {0}")]
@@ -23,6 +26,31 @@ pub enum SnifferError {
     /// Invalid configuration.
     #[error("Invalid configuration: {0}")]
     InvalidConfig(String),
+
+    /// The forced encoding does not match the data.
+    #[error(
+        "Data does not decode as {encoding}: {malformed} malformed sequences in {chars} characters"
+    )]
+    EncodingMismatch {
+        /// Name of the forced encoding.
+        encoding: &'static str,
+        /// Number of malformed byte sequences encountered.
+        malformed: usize,
+        /// Number of characters decoded.
+        chars: usize,
+    },
+}
+
+/// A cell that does not parse as its column's inferred type.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Cannot parse {value:?} in column {column} as {expected}")]
+pub struct CellError {
+    /// Zero-based column index.
+    pub column: usize,
+    /// The inferred column type.
+    pub expected: Type,
+    /// The raw cell value.
+    pub value: String,
 }
 
 /// Result type alias for sniffing operations.
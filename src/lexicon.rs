@@ -0,0 +1,387 @@
+//! Header-name likelihood for cells of a candidate header row.
+//!
+//! Used by header detection when every column is text and type-based checks
+//! give no signal. A cell scores highly if it contains a common column word
+//! from a small multilingual lexicon, or if it is shaped like an identifier
+//! (`snake_case`, `camelCase`, `customer_id`).
+
+/// Common column-name words in English, Spanish, Portuguese, French, German,
+/// Italian and Dutch. Matched against lowercased tokens.
+const HEADER_WORDS: &[&str] = &[
+    // English
+    "id",
+    "name",
+    "first",
+    "last",
+    "full",
+    "date",
+    "time",
+    "timestamp",
+    "year",
+    "month",
+    "day",
+    "amount",
+    "total",
+    "price",
+    "cost",
+    "qty",
+    "quantity",
+    "count",
+    "number",
+    "num",
+    "no",
+    "code",
+    "type",
+    "status",
+    "description",
+    "desc",
+    "title",
+    "email",
+    "mail",
+    "phone",
+    "mobile",
+    "fax",
+    "address",
+    "street",
+    "city",
+    "state",
+    "country",
+    "zip",
+    "postal",
+    "postcode",
+    "region",
+    "category",
+    "value",
+    "rate",
+    "percent",
+    "age",
+    "gender",
+    "sex",
+    "company",
+    "department",
+    "created",
+    "updated",
+    "modified",
+    "start",
+    "end",
+    "user",
+    "username",
+    "customer",
+    "product",
+    "item",
+    "order",
+    "account",
+    "balance",
+    "latitude",
+    "longitude",
+    "lat",
+    "lon",
+    "lng",
+    "url",
+    "comment",
+    "comments",
+    "notes",
+    "key",
+    "label",
+    "score",
+    "level",
+    "group",
+    "unit",
+    "units",
+    "size",
+    "weight",
+    "height",
+    "width",
+    "length",
+    "color",
+    "source",
+    "tag",
+    "version",
+    "owner",
+    // Spanish
+    "nombre",
+    "apellido",
+    "apellidos",
+    "fecha",
+    "cantidad",
+    "importe",
+    "monto",
+    "precio",
+    "código",
+    "codigo",
+    "dirección",
+    "direccion",
+    "ciudad",
+    "país",
+    "provincia",
+    "teléfono",
+    "correo",
+    "estado",
+    "tipo",
+    "descripción",
+    "descripcion",
+    "año",
+    "mes",
+    "día",
+    "edad",
+    "sexo",
+    "empresa",
+    "cliente",
+    "producto",
+    "número",
+    "numero",
+    "valor",
+    // Portuguese
+    "sobrenome",
+    "preço",
+    "preco",
+    "quantidade",
+    "endereço",
+    "endereco",
+    "cidade",
+    "telefone",
+    "ano",
+    "mês",
+    "dia",
+    // French
+    "nom",
+    "prénom",
+    "prenom",
+    "montant",
+    "prix",
+    "quantité",
+    "quantite",
+    "adresse",
+    "ville",
+    "pays",
+    "téléphone",
+    "telephone",
+    "courriel",
+    "numéro",
+    "statut",
+    "libellé",
+    "libelle",
+    "société",
+    "societe",
+    "âge",
+    "année",
+    "annee",
+    "mois",
+    "jour",
+    "valeur",
+    // German
+    "datum",
+    "betrag",
+    "preis",
+    "menge",
+    "anzahl",
+    "straße",
+    "strasse",
+    "ort",
+    "stadt",
+    "land",
+    "telefon",
+    "nummer",
+    "bezeichnung",
+    "beschreibung",
+    "kunde",
+    "firma",
+    "jahr",
+    "monat",
+    "alter",
+    "geschlecht",
+    "vorname",
+    "nachname",
+    "wert",
+    "summe",
+    "postleitzahl",
+    "plz",
+    // Italian
+    "nome",
+    "cognome",
+    "data",
+    "importo",
+    "prezzo",
+    "quantità",
+    "quantita",
+    "indirizzo",
+    "città",
+    "citta",
+    "paese",
+    "codice",
+    "stato",
+    "anno",
+    "mese",
+    "giorno",
+    "valore",
+    // Dutch
+    "naam",
+    "bedrag",
+    "prijs",
+    "aantal",
+    "adres",
+    "plaats",
+    "stad",
+    "telefoon",
+    "omschrijving",
+    "jaar",
+    "maand",
+    "dag",
+];
+
+/// Trailing tokens that mark a compound identifier (`customer_id`, `order_date`).
+const IDENTIFIER_SUFFIXES: &[&str] = &["id", "date", "at", "code", "no", "num", "name", "type"];
+
+/// Likelihood for a lexicon word inside a multi-word cell.
+const LEXICON_IN_PHRASE: f64 = 0.6;
+
+/// Cells longer than this are treated as data.
+const MAX_HEADER_LEN: usize = 40;
+
+/// Likelihood (0.0–1.0) that a single cell is a column name.
+pub fn header_likelihood(cell: &str) -> f64 {
+    let cell = cell.trim();
+    if cell.is_empty()
+        || cell.starts_with(|c: char| c.is_ascii_digit())
+        || cell.chars().count() > MAX_HEADER_LEN
+        || cell.contains('@')
+        || cell.contains("://")
+    {
+        return 0.0;
+    }
+
+    let tokens = tokenize(cell);
+    if tokens.iter().any(|t| HEADER_WORDS.contains(&t.as_str())) {
+        // Free text such as "Baker Street" can contain a column word too
+        return if cell.contains(char::is_whitespace) {
+            LEXICON_IN_PHRASE
+        } else {
+            1.0
+        };
+    }
+    shape_likelihood(cell, &tokens)
+}
+
+/// Mean header likelihood of the non-blank cells in a row.
+pub fn row_header_likelihood(row: &[String]) -> f64 {
+    let (sum, count) = row
+        .iter()
+        .filter(|cell| !cell.trim().is_empty())
+        .fold((0.0, 0), |(sum, count), cell| {
+            (sum + header_likelihood(cell), count + 1)
+        });
+    if count == 0 {
+        0.0
+    } else {
+        sum / f64::from(count)
+    }
+}
+
+/// Score a cell by identifier shape alone.
+fn shape_likelihood(cell: &str, tokens: &[String]) -> f64 {
+    let has_space = cell.contains(char::is_whitespace);
+    let has_digit = cell.chars().any(|c| c.is_ascii_digit());
+
+    if !has_space
+        && tokens.len() >= 2
+        && tokens
+            .last()
+            .is_some_and(|t| IDENTIFIER_SUFFIXES.contains(&t.as_str()))
+    {
+        return 0.9;
+    }
+
+    if !has_space && tokens.len() >= 2 && is_snake_case(cell) {
+        return 0.8;
+    }
+
+    if !has_space && tokens.len() >= 2 && !cell.contains(['_', '-']) && !has_digit {
+        // camelCase / PascalCase
+        return 0.7;
+    }
+
+    if has_digit {
+        return 0.0;
+    }
+
+    let words: Vec<&str> = cell.split_whitespace().collect();
+    if (2..=4).contains(&words.len())
+        && words
+            .iter()
+            .all(|w| w.chars().next().is_some_and(char::is_uppercase))
+    {
+        // Title Case
+        return 0.5;
+    }
+
+    if words.len() == 1 && cell.chars().count() <= 15 && cell.chars().all(char::is_alphabetic) {
+        return 0.3;
+    }
+
+    0.0
+}
+
+fn is_snake_case(cell: &str) -> bool {
+    cell.contains('_')
+        && cell
+            .chars()
+            .all(|c| c == '_' || c.is_ascii_digit() || c.is_lowercase())
+}
+
+/// Split a cell into lowercase word tokens on separators and camelCase humps.
+fn tokenize(cell: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+
+    for c in cell.chars() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower && !current.is_empty() {
+            tokens.push(std::mem::take(&mut current));
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(tokenize("customerId"), vec!["customer", "id"]);
+        assert_eq!(tokenize("Order_Date"), vec!["order", "date"]);
+        assert_eq!(tokenize("Código Postal"), vec!["código", "postal"]);
+    }
+
+    #[test]
+    fn test_header_likelihood() {
+        assert_eq!(header_likelihood("Datum"), 1.0);
+        assert_eq!(header_likelihood("nom"), 1.0);
+        assert_eq!(header_likelihood("warehouse_ref"), 0.8);
+        assert_eq!(header_likelihood("shipmentRef"), 0.7);
+        assert_eq!(header_likelihood("Alice"), 0.3);
+        assert_eq!(header_likelihood("Baker Street"), 0.6);
+        assert_eq!(header_likelihood("12 Baker Street"), 0.0);
+        assert_eq!(header_likelihood("alice@example.com"), 0.0);
+    }
+
+    #[test]
+    fn test_row_header_likelihood() {
+        let header = ["first_name", "last_name", "city"].map(String::from);
+        let data = ["Alice", "Smith", "Paris"].map(String::from);
+        assert!(row_header_likelihood(&header) > row_header_likelihood(&data) + 0.5);
+    }
+}
@@ -51,23 +51,41 @@
 //! 3. Scoring based on type detection (consistent data types within columns)
 //! 4. Selecting the dialect with the highest combined score
 
+#[cfg(feature = "async")]
+mod async_sniff;
 mod encoding;
 mod error;
 mod field_type;
+mod lexicon;
 pub mod metadata;
+mod reader;
 mod sample;
+mod scoring;
 mod sniffer;
+mod stream;
 mod tum;
+mod typed;
+mod writer;
 
 // Re-export public API (qsv-sniffer compatible)
-pub use error::{Result, SnifferError};
+pub use error::{CellError, Result, SnifferError};
 pub use field_type::Type;
-pub use metadata::{Dialect, Header, Metadata, Quote};
+pub use metadata::{
+    CandidateExplanation, Dialect, DialectCandidate, Explanation, FactorKind, FixedWidth,
+    FixedWidthColumn, Header, HeaderVote, Layout, LineTerminator, Metadata, Quote, ScoreFactor,
+    Separator,
+};
+pub use reader::SourceReader;
 pub use sample::{DatePreference, SampleSize};
+pub use scoring::ScoringConfig;
 pub use sniffer::Sniffer;
+pub use stream::SniffState;
+pub use typed::{Date, DateTime, TypedReader, TypedRecord, Value, parse_value};
+pub use writer::SinkWriter;
 
 // Re-export for advanced usage
 pub use encoding::{EncodingInfo, detect_encoding, is_utf8};
+pub use encoding_rs;
 
 #[cfg(test)]
 mod tests {
@@ -1,14 +1,17 @@
 //! csv-nose CLI - CSV dialect sniffer
 
 mod benchmark;
+mod calibrate;
 #[cfg(feature = "http")]
 mod http;
 
 use benchmark::{find_annotations, run_benchmark};
+use calibrate::run_calibration;
 use clap::Parser;
-use csv_nose::{DatePreference, Quote, SampleSize, Sniffer};
+use csv_nose::encoding_rs::Encoding;
+use csv_nose::{DatePreference, FactorKind, Quote, SampleSize, ScoringConfig, Separator, Sniffer};
 use std::fmt::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
 /// CSV dialect sniffer using the Table Uniformity Method.
@@ -19,18 +22,35 @@ use std::process::ExitCode;
 #[command(name = "csv-nose")]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Input CSV file(s) or URL(s) to sniff, or directory for benchmark mode
-    #[arg(required_unless_present = "benchmark")]
+    /// Input CSV file(s) or URL(s) to sniff, or directories for benchmark and calibrate modes
+    #[arg(required_unless_present_any = ["benchmark", "calibrate"])]
     files: Vec<String>,
 
     /// Run benchmark mode on a directory of test files
     #[arg(long)]
     benchmark: bool,
 
-    /// Path to annotations file for benchmark mode (auto-detected if not specified)
+    /// Tune the scoring weights and penalties on directories of annotated test files
+    #[arg(long, conflicts_with = "benchmark")]
+    calibrate: bool,
+
+    /// Path to annotations file for benchmark or calibrate mode (auto-detected if not specified)
     #[arg(long)]
     annotations: Option<PathBuf>,
 
+    /// Maximum number of coordinate-ascent rounds in calibrate mode
+    #[arg(long, default_value = "3")]
+    rounds: usize,
+
+    /// Write the calibrated scoring configuration to this file (default: stdout)
+    #[arg(short = 'o', long)]
+    output: Option<PathBuf>,
+
+    /// Load scoring weights, penalties and priorities from a configuration file
+    /// (as written by --calibrate)
+    #[arg(long)]
+    scoring: Option<PathBuf>,
+
     /// Number of records to sample (default: 100)
     #[arg(short = 'n', long, default_value = "100")]
     sample_records: usize,
@@ -47,10 +67,31 @@ struct Args {
     #[arg(short = 'd', long)]
     delimiter: Option<char>,
 
+    /// Candidate delimiters to test instead of the built-in set (e.g. ",;|";
+    /// escapes: \t, \\, \xNN)
+    #[arg(long)]
+    candidates: Option<String>,
+
     /// Force specific quote character (single character, or 'none')
     #[arg(short = 'q', long)]
     quote: Option<String>,
 
+    /// Force the source encoding by WHATWG label (e.g. windows-1252, iso-8859-15, gbk)
+    #[arg(long)]
+    encoding: Option<String>,
+
+    /// Treat the first row after the preamble as a header (skip header detection)
+    #[arg(long, conflicts_with = "no_header")]
+    has_header: bool,
+
+    /// Treat the first row after the preamble as data (skip header detection)
+    #[arg(long)]
+    no_header: bool,
+
+    /// Skip exactly this many preamble lines (skip preamble detection)
+    #[arg(long)]
+    preamble: Option<usize>,
+
     /// Use day-month-year date format preference (default: month-day-year)
     #[arg(long)]
     dmy: bool,
@@ -66,6 +107,10 @@ struct Args {
     /// Only output the detected delimiter character
     #[arg(long)]
     delimiter_only: bool,
+
+    /// Show every bonus and penalty applied to each candidate dialect (text and json output)
+    #[arg(long)]
+    explain: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
@@ -78,10 +123,13 @@ enum OutputFormat {
 fn main() -> ExitCode {
     let args = Args::parse();
 
-    // Handle benchmark mode
+    // Handle benchmark and calibrate modes
     if args.benchmark {
         return run_benchmark_cli(&args);
     }
+    if args.calibrate {
+        return run_calibrate_cli(&args);
+    }
 
     let mut exit_code = ExitCode::SUCCESS;
 
@@ -108,6 +156,39 @@ fn main() -> ExitCode {
     exit_code
 }
 
+/// Parse a `--candidates` list of single-byte delimiters, accepting `\t`,
+/// `\\` and `\xNN` escapes.
+fn parse_candidates(spec: &str) -> Result<Vec<u8>, String> {
+    let mut delimiters = Vec::new();
+    let mut chars = spec.chars();
+    while let Some(c) = chars.next() {
+        let delimiter = match c {
+            '\\' => match chars.next() {
+                Some('t') => b'\t',
+                Some('\\') => b'\\',
+                Some('x') => {
+                    let hex: String = chars.by_ref().take(2).collect();
+                    u8::from_str_radix(&hex, 16)
+                        .map_err(|_| format!("invalid \\x escape in candidates: \\x{hex}"))?
+                }
+                other => {
+                    return Err(format!(
+                        "invalid escape in candidates: \\{}",
+                        other.map(String::from).unwrap_or_default()
+                    ));
+                }
+            },
+            c if c.is_ascii() => c as u8,
+            c => return Err(format!("candidate delimiter must be a single byte: {c:?}")),
+        };
+        delimiters.push(delimiter);
+    }
+    if delimiters.is_empty() {
+        return Err("--candidates must list at least one delimiter".to_string());
+    }
+    Ok(delimiters)
+}
+
 /// Check if a path looks like a URL.
 fn is_url(path: &str) -> bool {
     path.starts_with("http://") || path.starts_with("https://")
@@ -145,11 +226,19 @@ fn run_benchmark_cli(args: &Args) -> ExitCode {
         return ExitCode::FAILURE;
     };
 
+    let scoring = match load_scoring(args) {
+        Ok(scoring) => scoring,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
     println!("Running benchmark on: {}", data_dir.display());
     println!("Using annotations: {}", annotations_path.display());
     println!();
 
-    match run_benchmark(&data_dir, &annotations_path) {
+    match run_benchmark(&data_dir, &annotations_path, &scoring) {
         Ok(result) => {
             result.print_details();
             result.print_summary();
@@ -162,6 +251,90 @@ fn run_benchmark_cli(args: &Args) -> ExitCode {
     }
 }
 
+fn run_calibrate_cli(args: &Args) -> ExitCode {
+    if args.files.is_empty() {
+        eprintln!("Error: calibrate mode requires one or more directory paths");
+        return ExitCode::FAILURE;
+    }
+    if args.annotations.is_some() && args.files.len() > 1 {
+        eprintln!("Error: --annotations can only be used with a single directory");
+        return ExitCode::FAILURE;
+    }
+
+    let mut datasets = Vec::with_capacity(args.files.len());
+    for file in &args.files {
+        let data_dir = PathBuf::from(file);
+        if is_url(file) || !data_dir.is_dir() {
+            eprintln!("Error: {file} is not a local directory");
+            return ExitCode::FAILURE;
+        }
+        let Some(annotations_path) = args
+            .annotations
+            .clone()
+            .or_else(|| find_annotations(&data_dir))
+        else {
+            eprintln!(
+                "Error: Could not find annotations file for {}",
+                data_dir.display()
+            );
+            eprintln!("Use --annotations to specify the path to the annotations file");
+            return ExitCode::FAILURE;
+        };
+        eprintln!(
+            "Calibrating on: {} (annotations: {})",
+            data_dir.display(),
+            annotations_path.display()
+        );
+        datasets.push((data_dir, annotations_path));
+    }
+
+    let start = match load_scoring(args) {
+        Ok(scoring) => scoring,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let datasets: Vec<(&Path, &Path)> = datasets
+        .iter()
+        .map(|(data_dir, annotations)| (data_dir.as_path(), annotations.as_path()))
+        .collect();
+    let calibration = match run_calibration(&datasets, start, args.rounds) {
+        Ok(calibration) => calibration,
+        Err(e) => {
+            eprintln!("Error running calibration: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    eprintln!(
+        "Training accuracy: {:.1}% -> {:.1}% on {} files (tuned and measured on the same files)",
+        calibration.baseline_accuracy() * 100.0,
+        calibration.accuracy() * 100.0,
+        calibration.total
+    );
+    let config_file = calibration.to_config_file();
+    match args.output {
+        Some(ref path) => {
+            if let Err(e) = std::fs::write(path, config_file) {
+                eprintln!("Error writing {}: {e}", path.display());
+                return ExitCode::FAILURE;
+            }
+            eprintln!("Wrote scoring configuration to {}", path.display());
+        }
+        None => print!("{config_file}"),
+    }
+    ExitCode::SUCCESS
+}
+
+/// The scoring configuration from `--scoring`, or the default.
+fn load_scoring(args: &Args) -> csv_nose::Result<ScoringConfig> {
+    args.scoring
+        .as_ref()
+        .map_or_else(|| Ok(ScoringConfig::default()), ScoringConfig::from_path)
+}
+
 fn sniff_file(path: &PathBuf, args: &Args) -> Result<(), Box<dyn std::error::Error>> {
     let mut sniffer = Sniffer::new();
 
@@ -184,6 +357,11 @@ fn sniff_file(path: &PathBuf, args: &Args) -> Result<(), Box<dyn std::error::Err
         sniffer.delimiter(delim as u8);
     }
 
+    // Configure candidate delimiters
+    if let Some(ref candidates) = args.candidates {
+        sniffer.candidate_delimiters(&parse_candidates(candidates)?);
+    }
+
     // Configure forced quote
     if let Some(ref quote_str) = args.quote {
         if quote_str.to_lowercase() == "none" {
@@ -193,12 +371,34 @@ fn sniff_file(path: &PathBuf, args: &Args) -> Result<(), Box<dyn std::error::Err
         }
     }
 
+    // Configure forced encoding
+    if let Some(ref label) = args.encoding {
+        let encoding = Encoding::for_label(label.as_bytes())
+            .ok_or_else(|| format!("unknown encoding label: {label}"))?;
+        sniffer.encoding(encoding);
+    }
+
+    // Configure forced header and preamble
+    if args.has_header {
+        sniffer.header(true);
+    } else if args.no_header {
+        sniffer.header(false);
+    }
+    if let Some(rows) = args.preamble {
+        sniffer.preamble_rows(rows);
+    }
+
+    sniffer.explain(args.explain);
+    if args.scoring.is_some() {
+        sniffer.scoring(load_scoring(args)?);
+    }
+
     // Sniff the file
     let metadata = sniffer.sniff_path(path)?;
 
     // Output based on format
     if args.delimiter_only {
-        println!("{}", metadata.dialect.delimiter as char);
+        println!("{}", metadata.dialect.separator);
         return Ok(());
     }
 
@@ -245,6 +445,11 @@ fn sniff_url(url: &str, args: &Args) -> Result<(), Box<dyn std::error::Error>> {
         sniffer.delimiter(delim as u8);
     }
 
+    // Configure candidate delimiters
+    if let Some(ref candidates) = args.candidates {
+        sniffer.candidate_delimiters(&parse_candidates(candidates)?);
+    }
+
     // Configure forced quote
     if let Some(ref quote_str) = args.quote {
         if quote_str.to_lowercase() == "none" {
@@ -254,12 +459,34 @@ fn sniff_url(url: &str, args: &Args) -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
+    // Configure forced encoding
+    if let Some(ref label) = args.encoding {
+        let encoding = Encoding::for_label(label.as_bytes())
+            .ok_or_else(|| format!("unknown encoding label: {label}"))?;
+        sniffer.encoding(encoding);
+    }
+
+    // Configure forced header and preamble
+    if args.has_header {
+        sniffer.header(true);
+    } else if args.no_header {
+        sniffer.header(false);
+    }
+    if let Some(rows) = args.preamble {
+        sniffer.preamble_rows(rows);
+    }
+
+    sniffer.explain(args.explain);
+    if args.scoring.is_some() {
+        sniffer.scoring(load_scoring(args)?);
+    }
+
     // Sniff the fetched bytes
     let metadata = sniffer.sniff_bytes(&fetch_result.data)?;
 
     // Output based on format
     if args.delimiter_only {
-        println!("{}", metadata.dialect.delimiter as char);
+        println!("{}", metadata.dialect.separator);
         return Ok(());
     }
 
@@ -274,25 +501,63 @@ fn sniff_url(url: &str, args: &Args) -> Result<(), Box<dyn std::error::Error>> {
 
 fn print_text_output(path: &str, metadata: &csv_nose::Metadata, verbose: bool) {
     println!("File: {path}");
-    println!("  Delimiter: {:?}", metadata.dialect.delimiter as char);
     println!(
-        "  Quote: {}",
-        match metadata.dialect.quote {
-            Quote::None => "none".to_string(),
-            Quote::Some(q) => format!("{:?}", q as char),
+        "  Delimiter: {}",
+        separator_label(&metadata.dialect.separator)
+    );
+    println!("  Quote: {}", quote_label(metadata.dialect.quote));
+    println!(
+        "  Escape: {}",
+        match metadata.dialect.escape {
+            Some(e) => format!("{:?}", e as char),
+            None if metadata.dialect.double_quote => "doubled quote".to_string(),
+            None => "none".to_string(),
         }
     );
+    println!("  Line terminator: {}", metadata.dialect.line_terminator);
+    println!(
+        "  Comment: {}",
+        metadata
+            .dialect
+            .comment
+            .map_or_else(|| "none".to_string(), |c| format!("{:?}", c as char))
+    );
     println!("  Has header: {}", metadata.dialect.header.has_header_row);
+    println!("  Header rows: {}", metadata.dialect.header.num_header_rows);
     println!(
         "  Preamble rows: {}",
         metadata.dialect.header.num_preamble_rows
     );
+    println!("  Footer rows: {}", metadata.dialect.header.num_footer_rows);
     println!("  Flexible: {}", metadata.dialect.flexible);
     println!("  UTF-8: {}", metadata.dialect.is_utf8);
+    println!(
+        "  Encoding: {}{}{}",
+        metadata.encoding.name(),
+        if metadata.encoding.has_bom {
+            " (BOM)"
+        } else {
+            ""
+        },
+        if metadata.encoding.had_errors {
+            " (malformed sequences)"
+        } else {
+            ""
+        }
+    );
     println!("  Fields: {}", metadata.num_fields);
     println!("  Avg record length: {} bytes", metadata.avg_record_len);
+    println!("  Confidence: {:.2}", metadata.confidence);
 
     if verbose {
+        let votes: Vec<String> = metadata
+            .dialect
+            .header
+            .column_votes
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        println!("  Header votes: {}", votes.join(", "));
         println!("  Field details:");
         for (i, (name, typ)) in metadata
             .fields
@@ -304,9 +569,45 @@ fn print_text_output(path: &str, metadata: &csv_nose::Metadata, verbose: bool) {
         }
     }
 
+    if let Some(ref explanation) = metadata.explanation {
+        println!("  Explanation (tie-break: {}):", explanation.tie_break);
+        for candidate in &explanation.candidates {
+            println!(
+                "    {} {}: gamma {:.4}",
+                separator_label(&candidate.separator),
+                quote_label(candidate.quote),
+                candidate.gamma
+            );
+            for factor in &candidate.factors {
+                println!(
+                    "      {} {:<24} {:.4}  {}",
+                    factor.kind, factor.name, factor.value, factor.reason
+                );
+            }
+        }
+    }
+
     println!();
 }
 
+/// Format a field separator for text output.
+fn separator_label(separator: &Separator) -> String {
+    match separator {
+        Separator::Byte(b) => format!("{:?}", *b as char),
+        Separator::Char(c) => format!("{c:?}"),
+        Separator::Sequence(bytes) => format!("{:?}", String::from_utf8_lossy(bytes)),
+        Separator::Whitespace => "whitespace".to_string(),
+    }
+}
+
+/// Format a quote setting for text output.
+fn quote_label(quote: Quote) -> String {
+    match quote {
+        Quote::None => "none".to_string(),
+        Quote::Some(q) => format!("{:?}", q as char),
+    }
+}
+
 /// Escape a string for JSON output (handles quotes, backslashes, and control characters).
 fn escape_json(s: &str) -> String {
     let mut result = String::with_capacity(s.len());
@@ -341,21 +642,47 @@ fn print_json_output(path: &str, metadata: &csv_nose::Metadata, verbose: bool) {
         Quote::None => "null".to_string(),
         Quote::Some(q) => format!("\"{}\"", q as char),
     };
+    let escape_str = metadata.dialect.escape.map_or_else(
+        || "null".to_string(),
+        |e| format!("\"{}\"", escape_json(&(e as char).to_string())),
+    );
+    let comment_str = metadata.dialect.comment.map_or_else(
+        || "null".to_string(),
+        |c| format!("\"{}\"", escape_json(&(c as char).to_string())),
+    );
 
     print!(
-        r#"{{"file":"{}","dialect":{{"delimiter":"{}","quote":{},"has_header":{},"preamble_rows":{},"flexible":{},"is_utf8":{}}},"num_fields":{},"avg_record_len":{}"#,
+        r#"{{"file":"{}","dialect":{{"delimiter":"{}","quote":{},"has_header":{},"header_rows":{},"escape":{},"double_quote":{},"line_terminator":"{}","comment":{},"preamble_rows":{},"footer_rows":{},"flexible":{},"is_utf8":{}}},"encoding":{{"name":"{}","has_bom":{},"had_errors":{}}},"num_fields":{},"avg_record_len":{},"confidence":{:.4}"#,
         escape_json(path),
-        metadata.dialect.delimiter as char,
+        metadata.dialect.separator,
         quote_str,
         metadata.dialect.header.has_header_row,
+        metadata.dialect.header.num_header_rows,
+        escape_str,
+        metadata.dialect.double_quote,
+        escape_json(metadata.dialect.line_terminator.as_str()),
+        comment_str,
         metadata.dialect.header.num_preamble_rows,
+        metadata.dialect.header.num_footer_rows,
         metadata.dialect.flexible,
         metadata.dialect.is_utf8,
+        metadata.encoding.name(),
+        metadata.encoding.has_bom,
+        metadata.encoding.had_errors,
         metadata.num_fields,
-        metadata.avg_record_len
+        metadata.avg_record_len,
+        metadata.confidence
     );
 
     if verbose {
+        print!(r#","header_votes":["#);
+        for (i, vote) in metadata.dialect.header.column_votes.iter().enumerate() {
+            if i > 0 {
+                print!(",");
+            }
+            print!(r#""{vote}""#);
+        }
+        print!("]");
         print!(r#","fields":["#);
         for (i, (name, typ)) in metadata
             .fields
@@ -375,6 +702,45 @@ fn print_json_output(path: &str, metadata: &csv_nose::Metadata, verbose: bool) {
         print!("]");
     }
 
+    if let Some(ref explanation) = metadata.explanation {
+        print!(
+            r#","explanation":{{"tie_break":{},"candidates":["#,
+            explanation.tie_break
+        );
+        for (i, candidate) in explanation.candidates.iter().enumerate() {
+            if i > 0 {
+                print!(",");
+            }
+            let quote = match candidate.quote {
+                Quote::None => "null".to_string(),
+                Quote::Some(q) => format!("\"{}\"", escape_json(&(q as char).to_string())),
+            };
+            print!(
+                r#"{{"delimiter":"{}","quote":{},"gamma":{:.6},"factors":["#,
+                escape_json(&candidate.separator.to_string()),
+                quote,
+                candidate.gamma
+            );
+            for (j, factor) in candidate.factors.iter().enumerate() {
+                if j > 0 {
+                    print!(",");
+                }
+                print!(
+                    r#"{{"name":"{}","kind":"{}","value":{:.6},"reason":"{}"}}"#,
+                    factor.name,
+                    match factor.kind {
+                        FactorKind::Additive => "additive",
+                        FactorKind::Multiplier => "multiplier",
+                    },
+                    factor.value,
+                    escape_json(factor.reason)
+                );
+            }
+            print!("]}}");
+        }
+        print!("]}}");
+    }
+
     println!("}}");
 }
 
@@ -390,20 +756,36 @@ fn print_csv_output(path: &str, metadata: &csv_nose::Metadata) {
     // CSV header (print only for first file or could be configured)
     if !HEADER_PRINTED.swap(true, Ordering::Relaxed) {
         println!(
-            "file,delimiter,quote,has_header,preamble_rows,flexible,is_utf8,num_fields,avg_record_len"
+            "file,delimiter,quote,has_header,header_rows,escape,double_quote,line_terminator,comment,preamble_rows,footer_rows,flexible,is_utf8,encoding,has_bom,encoding_errors,num_fields,avg_record_len,confidence"
         );
     }
 
     println!(
-        "{},{},{},{},{},{},{},{},{}",
+        "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{:.4}",
         escape_csv(path),
-        metadata.dialect.delimiter as char,
+        metadata.dialect.separator,
         quote_str,
         metadata.dialect.header.has_header_row,
+        metadata.dialect.header.num_header_rows,
+        metadata
+            .dialect
+            .escape
+            .map_or_else(String::new, |e| escape_csv(&(e as char).to_string())),
+        metadata.dialect.double_quote,
+        metadata.dialect.line_terminator,
+        metadata
+            .dialect
+            .comment
+            .map_or_else(String::new, |c| escape_csv(&(c as char).to_string())),
         metadata.dialect.header.num_preamble_rows,
+        metadata.dialect.header.num_footer_rows,
         metadata.dialect.flexible,
         metadata.dialect.is_utf8,
+        metadata.encoding.name(),
+        metadata.encoding.has_bom,
+        metadata.encoding.had_errors,
         metadata.num_fields,
-        metadata.avg_record_len
+        metadata.avg_record_len,
+        metadata.confidence
     );
 }
@@ -1,8 +1,11 @@
+use crate::encoding::EncodingInfo;
 use crate::field_type::Type;
+use crate::sample::DatePreference;
 use std::fmt;
 
 /// Metadata about a CSV file.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct Metadata {
     /// The detected CSV dialect.
     pub dialect: Dialect,
@@ -14,6 +17,25 @@ pub struct Metadata {
     pub fields: Vec<String>,
     /// Detected type for each field.
     pub types: Vec<Type>,
+    /// Source encoding of the data, whether it had a BOM, and whether
+    /// decoding hit malformed sequences.
+    pub encoding: EncodingInfo,
+    /// Every candidate dialect that was scored, ranked with the selected
+    /// dialect first and the remaining candidates by descending gamma.
+    pub candidates: Vec<DialectCandidate>,
+    /// Confidence in the detected dialect, from 0.0 (a guess) to 1.0 (no
+    /// competing dialect came close). Low values indicate the choice was
+    /// made by tie-breaking rather than by a clear score margin.
+    pub confidence: f64,
+    /// Per-candidate breakdown of every bonus and penalty applied during
+    /// scoring. Only populated when `Sniffer::explain(true)` is set.
+    pub explanation: Option<Explanation>,
+    /// The preamble lines as they appear in the sample (after the BOM,
+    /// decoded to UTF-8), including their line terminators.
+    pub preamble: Vec<u8>,
+    /// Order of ambiguous numeric dates set on the sniffer, used by
+    /// [`TypedReader`](crate::TypedReader) to parse `Date` columns.
+    pub date_preference: DatePreference,
 }
 
 impl Metadata {
@@ -31,15 +53,178 @@ impl Metadata {
             num_fields,
             fields,
             types,
+            encoding: EncodingInfo::new(true, false),
+            candidates: Vec::new(),
+            confidence: 1.0,
+            explanation: None,
+            preamble: Vec::new(),
+            date_preference: DatePreference::MdyFormat,
+        }
+    }
+}
+
+/// The kind of layout found by [`Sniffer::sniff_layout_bytes`](crate::Sniffer::sniff_layout_bytes)
+/// and related methods.
+// Returned once per sniff, so the size of `Metadata` is not worth a box
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+pub enum Layout {
+    /// Delimited records (CSV, TSV and the like).
+    Delimited(Metadata),
+    /// Columns at fixed character positions.
+    FixedWidth(FixedWidth),
+}
+
+/// The column layout of a fixed-width file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedWidth {
+    /// The columns, left to right.
+    pub columns: Vec<FixedWidthColumn>,
+    /// Whether the first line after the preamble holds the column names.
+    pub has_header_row: bool,
+    /// Number of lines to skip before the header or data.
+    pub num_preamble_rows: usize,
+    /// Line terminator used by records.
+    pub line_terminator: LineTerminator,
+    /// Source encoding of the data.
+    pub encoding: EncodingInfo,
+    /// The layout's gamma score, comparable with `DialectCandidate::gamma`.
+    pub gamma: f64,
+    /// Gamma of the best delimited dialect it was chosen over (0.0 if no
+    /// dialect could be scored).
+    pub delimited_gamma: f64,
+}
+
+/// A column of a fixed-width file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedWidthColumn {
+    /// Column name from the header line, or a generated `field_N`.
+    pub name: String,
+    /// Character offset of the column's first character within a line.
+    pub start: usize,
+    /// Character offset one past the column's last character.
+    pub end: usize,
+    /// Type inferred from the column's trimmed cells.
+    pub field_type: Type,
+}
+
+/// A candidate dialect together with its full score breakdown.
+///
+/// Useful for inspecting the runner-up when a detection looks wrong.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DialectCandidate {
+    /// Field delimiter character (the stand-in byte for multi-byte separators).
+    pub delimiter: u8,
+    /// The full field separator.
+    pub separator: Separator,
+    /// Quote character configuration.
+    pub quote: Quote,
+    /// The combined gamma score (higher is better).
+    pub gamma: f64,
+    /// Consistency score (tau_0).
+    pub tau_0: f64,
+    /// Dispersion score (tau_1).
+    pub tau_1: f64,
+    /// Type detection score.
+    pub type_score: f64,
+    /// Pattern specificity score.
+    pub pattern_score: f64,
+    /// Number of rows parsed.
+    pub num_rows: usize,
+    /// Modal (most common) field count.
+    pub num_fields: usize,
+    /// Whether the table has uniform field counts.
+    pub is_uniform: bool,
+}
+
+/// A trace of how every candidate dialect was scored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    /// Candidate traces in the same order as `Metadata::candidates`
+    /// (selected dialect first).
+    pub candidates: Vec<CandidateExplanation>,
+    /// Whether the selected dialect won on delimiter/quote priority because
+    /// its gamma was within the tie-break band of a competitor.
+    pub tie_break: bool,
+}
+
+/// The factors that produced a single candidate's gamma score.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CandidateExplanation {
+    /// Field delimiter character (the stand-in byte for multi-byte separators).
+    pub delimiter: u8,
+    /// The full field separator.
+    pub separator: Separator,
+    /// Quote character configuration.
+    pub quote: Quote,
+    /// The final gamma score.
+    pub gamma: f64,
+    /// Factors in the order they were applied. Additive terms are summed into
+    /// the raw score, then multipliers are applied in turn.
+    pub factors: Vec<ScoreFactor>,
+}
+
+/// How a `ScoreFactor` contributes to gamma.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FactorKind {
+    /// Added to the raw score.
+    Additive,
+    /// Multiplied into the score.
+    Multiplier,
+}
+
+/// A single bonus, penalty or score component applied to a candidate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreFactor {
+    /// Short machine-readable name (e.g. `delimiter_penalty`).
+    pub name: &'static str,
+    /// Whether the value is added or multiplied.
+    pub kind: FactorKind,
+    /// The numeric contribution.
+    pub value: f64,
+    /// Human-readable reason the factor was applied.
+    pub reason: &'static str,
+}
+
+impl ScoreFactor {
+    pub(crate) const fn additive(name: &'static str, value: f64, reason: &'static str) -> Self {
+        Self {
+            name,
+            kind: FactorKind::Additive,
+            value,
+            reason,
+        }
+    }
+
+    pub(crate) const fn multiplier(name: &'static str, value: f64, reason: &'static str) -> Self {
+        Self {
+            name,
+            kind: FactorKind::Multiplier,
+            value,
+            reason,
+        }
+    }
+}
+
+impl fmt::Display for FactorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Additive => write!(f, "+"),
+            Self::Multiplier => write!(f, "x"),
         }
     }
 }
 
 /// CSV dialect specification.
 #[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
 pub struct Dialect {
-    /// Field delimiter character.
+    /// Field delimiter character. For multi-byte separators this is a
+    /// stand-in byte absent from the sample, which
+    /// [`SourceReader`](crate::SourceReader) substitutes for the separator.
     pub delimiter: u8,
+    /// The full field separator as it appears in the data.
+    pub separator: Separator,
     /// Header configuration.
     pub header: Header,
     /// Quote character configuration.
@@ -48,16 +233,33 @@ pub struct Dialect {
     pub flexible: bool,
     /// Whether the file is valid UTF-8.
     pub is_utf8: bool,
+    /// Escape character for quotes inside quoted fields (`Some(b'\\')` for
+    /// backslash-escaped exports), or `None` when quotes are escaped by doubling.
+    pub escape: Option<u8>,
+    /// Whether quotes inside quoted fields are escaped by doubling them
+    /// (RFC 4180).
+    pub double_quote: bool,
+    /// Line terminator used by records (quoted line breaks are ignored).
+    pub line_terminator: LineTerminator,
+    /// Comment character, if the file contains comment lines that cannot be
+    /// data rows. Multi-byte markers such as `//` are only skipped as preamble
+    /// and leave this `None`.
+    pub comment: Option<u8>,
 }
 
 impl Default for Dialect {
     fn default() -> Self {
         Self {
             delimiter: b',',
+            separator: Separator::Byte(b','),
             header: Header::default(),
             quote: Quote::Some(b'"'),
             flexible: false,
             is_utf8: true,
+            escape: None,
+            double_quote: true,
+            line_terminator: LineTerminator::LF,
+            comment: None,
         }
     }
 }
@@ -73,21 +275,140 @@ impl Dialect {
     ) -> Self {
         Self {
             delimiter,
+            separator: Separator::Byte(delimiter),
             header,
             quote,
             flexible,
             is_utf8,
+            escape: None,
+            double_quote: true,
+            line_terminator: LineTerminator::LF,
+            comment: None,
         }
     }
 }
 
+/// Field separator of a dialect.
+///
+/// `csv` only handles single-byte delimiters, so multi-byte separators are
+/// read by translating them to `Dialect::delimiter` first.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Separator {
+    /// A single byte (every ASCII delimiter).
+    Byte(u8),
+    /// A non-ASCII Unicode character, encoded as 2-4 bytes of UTF-8
+    /// (e.g. `§`, `¦`, `→`).
+    Char(char),
+    /// A run of two or more ASCII characters (e.g. `::`, `~|~`).
+    Sequence(Vec<u8>),
+    /// One or more spaces or tabs, like `sep=r"\s+"` in pandas. Blanks at
+    /// the start and end of a line do not separate fields, so aligned text
+    /// tables (`ps`, `df`, `.dat` files) split into uniform rows.
+    Whitespace,
+}
+
+impl Default for Separator {
+    fn default() -> Self {
+        Separator::Byte(b',')
+    }
+}
+
+impl Separator {
+    /// Returns the separator byte if it is a single byte.
+    pub fn as_byte(&self) -> Option<u8> {
+        match self {
+            Separator::Byte(b) => Some(*b),
+            Separator::Char(_) | Separator::Sequence(_) | Separator::Whitespace => None,
+        }
+    }
+
+    /// Returns the separator as it appears in UTF-8 data (a single space for
+    /// `Whitespace`).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Separator::Byte(b) => vec![*b],
+            Separator::Char(c) => c.to_string().into_bytes(),
+            Separator::Sequence(bytes) => bytes.clone(),
+            Separator::Whitespace => vec![b' '],
+        }
+    }
+}
+
+impl fmt::Display for Separator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Separator::Byte(b) => write!(f, "{}", *b as char),
+            Separator::Char(c) => write!(f, "{c}"),
+            Separator::Sequence(bytes) => write!(f, "{}", String::from_utf8_lossy(bytes)),
+            Separator::Whitespace => write!(f, "\\s+"),
+        }
+    }
+}
+
+/// Line terminator sequences.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LineTerminator {
+    /// Unix-style line ending (\n).
+    #[default]
+    LF,
+    /// Windows-style line ending (\r\n).
+    CRLF,
+    /// Old Mac-style line ending (\r).
+    CR,
+    /// Records end with more than one of the above styles.
+    Mixed,
+}
+
+impl LineTerminator {
+    /// Returns the byte sequence for this line terminator.
+    ///
+    /// `Mixed` returns `\n`, the terminator to use when rewriting the file.
+    pub const fn as_bytes(&self) -> &'static [u8] {
+        match self {
+            LineTerminator::LF | LineTerminator::Mixed => b"\n",
+            LineTerminator::CRLF => b"\r\n",
+            LineTerminator::CR => b"\r",
+        }
+    }
+
+    /// Returns the string representation.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            LineTerminator::LF => "\\n",
+            LineTerminator::CRLF => "\\r\\n",
+            LineTerminator::CR => "\\r",
+            LineTerminator::Mixed => "mixed",
+        }
+    }
+}
+
+impl fmt::Display for LineTerminator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
 /// Header configuration for a CSV file.
 #[derive(Debug, Clone, PartialEq, Default)]
+#[non_exhaustive]
 pub struct Header {
     /// Whether the CSV has a header row.
     pub has_header_row: bool,
+    /// Number of stacked header rows (0 without a header, 1 for a single
+    /// header row, up to 3 with grouping and units rows).
+    pub num_header_rows: usize,
     /// Number of rows to skip before the data (preamble/comment rows).
     pub num_preamble_rows: usize,
+    /// Number of physical lines spanned by the preamble rows, including blank
+    /// lines between them; readers skip this many lines.
+    pub num_preamble_lines: usize,
+    /// Number of trailing rows after the data (summary, trailer and blank
+    /// padding rows). Only detected when the sample reaches the end of input.
+    pub num_footer_rows: usize,
+    /// Per-column vote on whether the first row is a header, comparing each
+    /// cell against the type and length of the values below it.
+    pub column_votes: Vec<HeaderVote>,
 }
 
 impl Header {
@@ -95,7 +416,32 @@ impl Header {
     pub const fn new(has_header_row: bool, num_preamble_rows: usize) -> Self {
         Self {
             has_header_row,
+            num_header_rows: has_header_row as usize,
             num_preamble_rows,
+            num_preamble_lines: num_preamble_rows,
+            num_footer_rows: 0,
+            column_votes: Vec::new(),
+        }
+    }
+}
+
+/// A single column's vote on whether the first row is a header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeaderVote {
+    /// The cell does not fit the column's type or fixed length.
+    Header,
+    /// The cell fits the column like any other value.
+    Data,
+    /// The column gives no evidence (variable-length text, or a blank cell).
+    Abstain,
+}
+
+impl fmt::Display for HeaderVote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Header => write!(f, "header"),
+            Self::Data => write!(f, "data"),
+            Self::Abstain => write!(f, "abstain"),
         }
     }
 }
@@ -0,0 +1,344 @@
+//! Building configured `csv::Reader`s from sniffed metadata.
+
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+use encoding_rs::{Decoder, Encoding};
+
+use crate::encoding::has_utf8_bom;
+use crate::error::Result;
+use crate::metadata::{Dialect, Metadata, Quote};
+use crate::sniffer::{READ_CHUNK_SIZE, Sniffer};
+use crate::tum::separator::SeparatorTranslator;
+
+impl Dialect {
+    /// A `csv::ReaderBuilder` configured with this dialect's delimiter,
+    /// quoting, escape style, comment character, flexibility and header flag.
+    pub fn reader_builder(&self) -> csv::ReaderBuilder {
+        let mut builder = csv::ReaderBuilder::new();
+        builder
+            .delimiter(self.delimiter)
+            .has_headers(self.header.has_header_row)
+            .flexible(self.flexible)
+            .comment(self.comment)
+            .double_quote(self.double_quote)
+            .escape(self.escape);
+        match self.quote {
+            Quote::None => {
+                builder.quoting(false);
+            }
+            Quote::Some(q) => {
+                builder.quoting(true).quote(q);
+            }
+        }
+        builder
+    }
+}
+
+impl Metadata {
+    /// A `csv::ReaderBuilder` configured with the detected dialect.
+    ///
+    /// The builder does not skip the BOM or preamble, nor transcode; use
+    /// [`open_reader`](Self::open_reader) or [`reader_from`](Self::reader_from)
+    /// for that.
+    pub fn reader_builder(&self) -> csv::ReaderBuilder {
+        self.dialect.reader_builder()
+    }
+
+    /// Open the file at `path` as a `csv::Reader` positioned at the header.
+    ///
+    /// See [`reader_from`](Self::reader_from).
+    pub fn open_reader<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<csv::Reader<SourceReader<BufReader<File>>>> {
+        let file = File::open(path.as_ref())?;
+        self.reader_from(BufReader::new(file))
+    }
+
+    /// Wrap `reader` (positioned at the start of the input) in a `csv::Reader`
+    /// positioned at the header.
+    ///
+    /// The BOM and the `num_preamble_lines` preamble lines are skipped, and
+    /// non-UTF-8 input is transcoded on the fly from the detected encoding.
+    /// A multi-byte separator is replaced by the `delimiter` stand-in byte.
+    /// With stacked header rows, the headers are the combined `fields` and
+    /// the first record is the first data row. Footer rows are not removed.
+    pub fn reader_from<R: Read>(&self, reader: R) -> Result<csv::Reader<SourceReader<R>>> {
+        let translator = self.dialect.separator.as_byte().is_none().then(|| {
+            SeparatorTranslator::new(
+                &self.dialect.separator,
+                self.dialect.delimiter,
+                self.dialect.quote,
+                self.dialect.escape,
+            )
+        });
+        let source = SourceReader::with_translator(
+            reader,
+            self.encoding.encoding,
+            self.dialect.header.num_preamble_lines,
+            translator,
+        )?;
+        let mut csv_reader = self.reader_builder().from_reader(source);
+
+        if self.dialect.header.num_header_rows > 1 {
+            // Consume the stacked rows and report the combined names instead
+            csv_reader.headers()?;
+            let mut record = csv::ByteRecord::new();
+            for _ in 1..self.dialect.header.num_header_rows {
+                csv_reader.read_byte_record(&mut record)?;
+            }
+            csv_reader.set_headers(csv::StringRecord::from(self.fields.clone()));
+        }
+
+        Ok(csv_reader)
+    }
+}
+
+impl Sniffer {
+    /// Sniff the file at `path` and open it as a `csv::Reader` positioned at
+    /// the header.
+    pub fn sniff_and_open<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+    ) -> Result<(Metadata, csv::Reader<SourceReader<BufReader<File>>>)> {
+        let metadata = self.sniff_path(path.as_ref())?;
+        let reader = metadata.open_reader(path)?;
+        Ok((metadata, reader))
+    }
+}
+
+/// A reader over the original input that strips the BOM, transcodes to UTF-8,
+/// skips preamble lines and translates multi-byte separators.
+///
+/// Created by [`Metadata::open_reader`] and [`Metadata::reader_from`].
+pub struct SourceReader<R> {
+    /// The underlying reader.
+    inner: R,
+    /// Decoder for non-UTF-8 input; `None` passes UTF-8 bytes through.
+    decoder: Option<Decoder>,
+    /// Raw bytes read from `inner`.
+    raw: Vec<u8>,
+    /// Decoded bytes not yet returned.
+    out: Vec<u8>,
+    /// Position of the next byte of `out` to return.
+    out_pos: usize,
+    /// Preamble lines still to skip.
+    skip_lines: usize,
+    /// Whether the last skipped line ended with `\r` (a following `\n` belongs to it).
+    after_cr: bool,
+    /// Whether `inner` is exhausted.
+    eof: bool,
+    /// Replaces a multi-byte separator with the dialect's stand-in byte.
+    translator: Option<SeparatorTranslator>,
+}
+
+impl<R: Read> SourceReader<R> {
+    /// Wrap `inner`, decoding from `encoding` and skipping `skip_lines` lines.
+    pub fn new(inner: R, encoding: &'static Encoding, skip_lines: usize) -> io::Result<Self> {
+        Self::with_translator(inner, encoding, skip_lines, None)
+    }
+
+    /// Like [`new`](Self::new), also translating the separator after the
+    /// preamble.
+    pub(crate) fn with_translator(
+        mut inner: R,
+        encoding: &'static Encoding,
+        skip_lines: usize,
+        translator: Option<SeparatorTranslator>,
+    ) -> io::Result<Self> {
+        let mut out = Vec::new();
+        let decoder = if encoding == encoding_rs::UTF_8 {
+            // Strip a UTF-8 BOM up front; the bytes are otherwise passed through
+            let mut prefix = [0u8; 3];
+            let mut len = 0;
+            while len < prefix.len() {
+                match inner.read(&mut prefix[len..]) {
+                    Ok(0) => break,
+                    Ok(n) => len += n,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            if !has_utf8_bom(&prefix[..len]) {
+                out.extend_from_slice(&prefix[..len]);
+            }
+            None
+        } else {
+            // BOM sniffing matches `Encoding::decode` used during detection
+            Some(encoding.new_decoder())
+        };
+
+        let mut reader = Self {
+            inner,
+            decoder,
+            raw: vec![0u8; READ_CHUNK_SIZE],
+            out,
+            out_pos: 0,
+            skip_lines,
+            after_cr: false,
+            eof: false,
+            translator,
+        };
+        reader.process();
+        Ok(reader)
+    }
+
+    /// Consume the reader, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Read and decode the next chunk of input into `out`.
+    fn fill(&mut self) -> io::Result<()> {
+        let bytes_read = loop {
+            match self.inner.read(&mut self.raw) {
+                Ok(n) => break n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        };
+        let last = bytes_read == 0;
+
+        self.out.clear();
+        self.out_pos = 0;
+        match &mut self.decoder {
+            Some(decoder) => {
+                let capacity = decoder
+                    .max_utf8_buffer_length(bytes_read)
+                    .unwrap_or(bytes_read * 3)
+                    .max(4);
+                self.out.resize(capacity, 0);
+                let (_, _, written, _) =
+                    decoder.decode_to_utf8(&self.raw[..bytes_read], &mut self.out, last);
+                self.out.truncate(written);
+            }
+            None => self.out.extend_from_slice(&self.raw[..bytes_read]),
+        }
+        self.eof = last;
+        self.process();
+        Ok(())
+    }
+
+    /// Skip preamble lines in freshly decoded `out`, then translate the
+    /// separator in the rest.
+    fn process(&mut self) {
+        self.skip_preamble();
+        if let Some(translator) = &mut self.translator {
+            let mut translated = Vec::with_capacity(self.out.len() - self.out_pos);
+            translator.translate(&self.out[self.out_pos..], &mut translated, self.eof);
+            self.out = translated;
+            self.out_pos = 0;
+        }
+    }
+
+    /// Drop preamble lines from the front of `out`.
+    fn skip_preamble(&mut self) {
+        while self.out_pos < self.out.len() && (self.skip_lines > 0 || self.after_cr) {
+            let b = self.out[self.out_pos];
+            if self.after_cr {
+                self.after_cr = false;
+                if b == b'\n' {
+                    self.out_pos += 1;
+                }
+                continue;
+            }
+            self.out_pos += 1;
+            if b == b'\n' || b == b'\r' {
+                self.skip_lines -= 1;
+                self.after_cr = b == b'\r';
+            }
+        }
+    }
+}
+
+impl<R: Read> Read for SourceReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if self.out_pos < self.out.len() {
+                let n = buf.len().min(self.out.len() - self.out_pos);
+                buf[..n].copy_from_slice(&self.out[self.out_pos..self.out_pos + n]);
+                self.out_pos += n;
+                return Ok(n);
+            }
+            if self.eof || buf.is_empty() {
+                return Ok(0);
+            }
+            self.fill()?;
+        }
+    }
+}
+
+impl<R> std::fmt::Debug for SourceReader<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SourceReader")
+            .field("transcoding", &self.decoder.is_some())
+            .field("skip_lines", &self.skip_lines)
+            .field("translating", &self.translator.is_some())
+            .field("eof", &self.eof)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_all<R: Read>(mut reader: R) -> Vec<u8> {
+        let mut out = Vec::new();
+        reader.read_to_end(&mut out).unwrap();
+        out
+    }
+
+    #[test]
+    fn test_source_reader_skips_bom_and_lines() {
+        let data = b"\xEF\xBB\xBFtitle\r\n\r\na,b\r\n1,2\r\n";
+        let reader = SourceReader::new(&data[..], encoding_rs::UTF_8, 2).unwrap();
+        assert_eq!(read_all(reader), b"a,b\r\n1,2\r\n");
+
+        let reader = SourceReader::new(&b"x\ry\rz"[..], encoding_rs::UTF_8, 1).unwrap();
+        assert_eq!(read_all(reader), b"y\rz");
+    }
+
+    #[test]
+    fn test_source_reader_transcodes() {
+        // "café;1\n" in windows-1252
+        let data = b"caf\xE9;1\n";
+        let reader = SourceReader::new(&data[..], encoding_rs::WINDOWS_1252, 0).unwrap();
+        assert_eq!(read_all(reader), "café;1\n".as_bytes());
+
+        // UTF-16LE with BOM
+        let data = b"\xFF\xFEa\x00,\x00b\x00\n\x00";
+        let reader = SourceReader::new(&data[..], encoding_rs::UTF_16LE, 0).unwrap();
+        assert_eq!(read_all(reader), b"a,b\n");
+    }
+
+    #[test]
+    fn test_source_reader_translates_separator() {
+        let translator = SeparatorTranslator::new(
+            &crate::metadata::Separator::Char('¦'),
+            b'\x1f',
+            Quote::Some(b'"'),
+            None,
+        );
+        let data = "x¦y\na¦\"b¦c\"\n".as_bytes();
+        let reader =
+            SourceReader::with_translator(data, encoding_rs::UTF_8, 1, Some(translator)).unwrap();
+        assert_eq!(read_all(reader), "a\x1f\"b¦c\"\n".as_bytes());
+    }
+
+    #[test]
+    fn test_reader_builder_backslash_escape() {
+        let dialect = Dialect {
+            escape: Some(b'\\'),
+            double_quote: false,
+            ..Dialect::default()
+        };
+        let mut reader = dialect
+            .reader_builder()
+            .from_reader(&b"a,b\n\"say \\\"hi\\\"\",2\n"[..]);
+        let record = reader.records().nth(1).unwrap().unwrap();
+        assert_eq!(&record[0], "say \"hi\"");
+    }
+}
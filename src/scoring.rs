@@ -0,0 +1,506 @@
+//! Tunable weights, penalties and priorities used to score candidate dialects.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+use crate::error::{Result, SnifferError};
+use crate::metadata::{Quote, Separator};
+
+/// Names of delimiters in configuration keys; other bytes are written `0xNN`.
+const DELIMITER_NAMES: &[(u8, &str)] = &[
+    (b',', "comma"),
+    (b';', "semicolon"),
+    (b'\t', "tab"),
+    (b' ', "space"),
+    (b'|', "pipe"),
+    (b':', "colon"),
+    (b'^', "caret"),
+    (b'~', "tilde"),
+    (b'#', "hash"),
+    (b'&', "ampersand"),
+    (b'/', "slash"),
+];
+
+/// The built-in scoring configuration, shared by sniffers that do not set one.
+pub(crate) static DEFAULT_SCORING: LazyLock<ScoringConfig> = LazyLock::new(ScoringConfig::default);
+
+/// Weights, penalties and tie-break priorities of the gamma score.
+///
+/// Gamma is computed as
+///
+/// ```text
+/// (uniformity_weight * sqrt(tau_0 * tau_1) + type_weight * type_score
+///     + pattern_weight * pattern_score + row bonus + field bonus)
+///     * penalties
+/// ```
+///
+/// and dialects whose gamma ratio exceeds `tie_break_ratio` are ranked by
+/// delimiter priority, then quote priority. `Default` reproduces the built-in
+/// scoring exactly; set a modified copy with
+/// [`Sniffer::scoring`](crate::Sniffer::scoring):
+///
+/// ```
+/// use csv_nose::{ScoringConfig, Sniffer};
+///
+/// // Let semicolons win ties against commas
+/// let mut scoring = ScoringConfig::default();
+/// scoring.delimiter_priorities.insert(b';', 11);
+///
+/// let mut sniffer = Sniffer::new();
+/// sniffer.scoring(scoring);
+/// ```
+///
+/// A configuration can also be saved with `to_string()` and loaded with
+/// [`from_path`](Self::from_path) or `parse()`. The format has one
+/// `key = value` line per setting (see [`parameters`](Self::parameters));
+/// `#` starts a comment line and settings not listed keep their default:
+///
+/// ```text
+/// # European feeds
+/// delimiter_priority.semicolon = 11
+/// delimiter_penalty.pipe = 0.9
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoringConfig {
+    /// Weight of the uniformity term `sqrt(tau_0 * tau_1)`.
+    pub uniformity_weight: f64,
+    /// Weight of the per-column type consistency score.
+    pub type_weight: f64,
+    /// Weight of the type pattern specificity score.
+    pub pattern_weight: f64,
+    /// Bonus reached at `row_bonus_rows` rows, scaled linearly below.
+    pub row_bonus: f64,
+    /// Number of rows at which `row_bonus` is reached (0 is treated as 1).
+    pub row_bonus_rows: usize,
+    /// Bonus reached at `field_bonus_fields` fields, scaled linearly below;
+    /// single-field tables get none.
+    pub field_bonus: f64,
+    /// Number of fields at which `field_bonus` is reached (0 is treated as 1).
+    pub field_bonus_fields: usize,
+    /// Multiplier for tables whose modal field count is 1.
+    pub single_field_penalty: f64,
+    /// Multiplier for tables with more than 50 fields.
+    pub wide_table_penalty: f64,
+    /// Multiplier for tables with more than 100 fields.
+    pub very_wide_table_penalty: f64,
+    /// Multiplier for samples with fewer than 3 rows.
+    pub tiny_sample_penalty: f64,
+    /// Multiplier for samples with 3 or 4 rows.
+    pub small_sample_penalty: f64,
+    /// Prior multiplier per single-byte delimiter (1.0 = no penalty).
+    pub delimiter_penalties: HashMap<u8, f64>,
+    /// Prior for `#` in place of its `delimiter_penalties` entry when the
+    /// table has at least 3 fields and 50 rows.
+    pub hash_relaxed_penalty: f64,
+    /// Prior for delimiters without a `delimiter_penalties` entry.
+    pub unknown_delimiter_penalty: f64,
+    /// Prior for delimiters supplied with
+    /// [`Sniffer::candidate_delimiters`](crate::Sniffer::candidate_delimiters)
+    /// that have no `delimiter_penalties` entry.
+    pub custom_delimiter_penalty: f64,
+    /// Prior for the section sign `§` as a separator.
+    pub section_sign_penalty: f64,
+    /// Prior for other Unicode separators.
+    pub unicode_separator_penalty: f64,
+    /// Tie-break priority per single-byte delimiter (higher is preferred,
+    /// missing entries are 0).
+    pub delimiter_priorities: HashMap<u8, u8>,
+    /// Tie-break priority of the section sign `§` (other Unicode separators
+    /// have priority 0).
+    pub section_sign_priority: u8,
+    /// Tie-break priority of `Quote::Some(b'"')`.
+    pub double_quote_priority: u8,
+    /// Tie-break priority of `Quote::Some(b'\'')`.
+    pub single_quote_priority: u8,
+    /// Tie-break priority of `Quote::None`.
+    pub no_quote_priority: u8,
+    /// Gamma ratio (runner-up / best) above which two dialects are considered
+    /// tied and ranked by priority.
+    pub tie_break_ratio: f64,
+}
+
+impl Default for ScoringConfig {
+    fn default() -> Self {
+        Self {
+            uniformity_weight: 0.5,
+            type_weight: 0.3,
+            pattern_weight: 0.1,
+            row_bonus: 0.1,
+            row_bonus_rows: 20,
+            field_bonus: 0.2,
+            field_bonus_fields: 10,
+            single_field_penalty: 0.5,
+            wide_table_penalty: 0.8,
+            very_wide_table_penalty: 0.5,
+            tiny_sample_penalty: 0.80,
+            small_sample_penalty: 0.90,
+            // Penalties for uncommon delimiters keep rare characters from
+            // winning due to accidental patterns
+            delimiter_penalties: HashMap::from([
+                // Common delimiters - no penalty
+                (b',', 1.0),
+                (b';', 1.0),
+                (b'\t', 1.0),
+                (b'|', 0.98), // Pipe - slight penalty
+                (b':', 0.90), // Colon - moderate penalty (often in timestamps)
+                (b' ', 0.75), // Space - significant penalty (often in text)
+                // Rare delimiters
+                (b'^', 0.80),
+                (b'~', 0.80),
+                // Hash - strict default: treat `#` as a comment marker unless
+                // proven otherwise (see `hash_relaxed_penalty`)
+                (b'#', 0.60),
+                (b'&', 0.60), // Ampersand - very rare
+                (b'/', 0.65), // Forward slash - rare, often in paths/dates
+            ]),
+            hash_relaxed_penalty: 0.85,
+            unknown_delimiter_penalty: 0.70,
+            custom_delimiter_penalty: 1.0,
+            section_sign_penalty: 0.78,
+            unicode_separator_penalty: 0.70,
+            // Common delimiters like comma are preferred over rare ones like
+            // space or &
+            delimiter_priorities: HashMap::from([
+                (b',', 10), // Comma - most common, highest priority
+                (b';', 9),  // Semicolon - common in European locales
+                (b'\t', 8), // Tab - TSV files
+                // Pipe - common in data exports; intentionally tied with tab (both are
+                // respectable standard delimiters); tie resolved by iteration order
+                (b'|', 8),
+                (b':', 4), // Colon - sometimes used, but also appears in timestamps
+                (b'^', 3), // Caret - rare
+                (b'~', 3), // Tilde - rare
+                (b'/', 2), // Forward slash - rare
+                (b' ', 2), // Space - very rare as delimiter, often appears in text
+                (b'#', 1), // Hash - very rare, often used for comments
+                (b'&', 1), // Ampersand - very rare
+            ]),
+            section_sign_priority: 2,
+            // Double-quote is the standard default and should be preferred
+            double_quote_priority: 3,
+            single_quote_priority: 2,
+            no_quote_priority: 1,
+            tie_break_ratio: 0.95,
+        }
+    }
+}
+
+impl ScoringConfig {
+    /// Tie-break priority of a single-byte delimiter.
+    pub fn delimiter_priority(&self, delimiter: u8) -> u8 {
+        self.delimiter_priorities
+            .get(&delimiter)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Tie-break priority of a separator. Multi-character separators take
+    /// the priority of their highest-priority character, and whitespace runs
+    /// that of space.
+    pub fn separator_priority(&self, separator: &Separator) -> u8 {
+        match separator {
+            Separator::Byte(delimiter) => self.delimiter_priority(*delimiter),
+            Separator::Char('§') => self.section_sign_priority,
+            Separator::Char(_) => 0,
+            Separator::Sequence(bytes) => self
+                .sequence_delimiter(bytes)
+                .map_or(0, |b| self.delimiter_priority(b)),
+            Separator::Whitespace => self.delimiter_priority(b' '),
+        }
+    }
+
+    /// Tie-break priority of a quote setting.
+    pub fn quote_priority(&self, quote: Quote) -> u8 {
+        match quote {
+            Quote::Some(b'"') => self.double_quote_priority,
+            Quote::Some(b'\'') => self.single_quote_priority,
+            Quote::None => self.no_quote_priority,
+            Quote::Some(_) => 0,
+        }
+    }
+
+    /// Load a configuration file written by `to_string()` or
+    /// `csv-nose --calibrate`.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self> {
+        std::fs::read_to_string(path)?.parse()
+    }
+
+    /// Every setting as a `(key, value)` pair, in configuration-file order.
+    ///
+    /// Delimiter table entries are keyed `delimiter_penalty.<name>` and
+    /// `delimiter_priority.<name>`, where `<name>` is `comma`, `semicolon`,
+    /// `tab`, `space`, `pipe`, `colon`, `caret`, `tilde`, `hash`,
+    /// `ampersand`, `slash` or `0xNN`.
+    pub fn parameters(&self) -> Vec<(String, f64)> {
+        let mut parameters: Vec<(String, f64)> = [
+            ("uniformity_weight", self.uniformity_weight),
+            ("type_weight", self.type_weight),
+            ("pattern_weight", self.pattern_weight),
+            ("row_bonus", self.row_bonus),
+            ("row_bonus_rows", self.row_bonus_rows as f64),
+            ("field_bonus", self.field_bonus),
+            ("field_bonus_fields", self.field_bonus_fields as f64),
+            ("single_field_penalty", self.single_field_penalty),
+            ("wide_table_penalty", self.wide_table_penalty),
+            ("very_wide_table_penalty", self.very_wide_table_penalty),
+            ("tiny_sample_penalty", self.tiny_sample_penalty),
+            ("small_sample_penalty", self.small_sample_penalty),
+            ("hash_relaxed_penalty", self.hash_relaxed_penalty),
+            ("unknown_delimiter_penalty", self.unknown_delimiter_penalty),
+            ("custom_delimiter_penalty", self.custom_delimiter_penalty),
+            ("section_sign_penalty", self.section_sign_penalty),
+            ("unicode_separator_penalty", self.unicode_separator_penalty),
+            (
+                "section_sign_priority",
+                f64::from(self.section_sign_priority),
+            ),
+            (
+                "double_quote_priority",
+                f64::from(self.double_quote_priority),
+            ),
+            (
+                "single_quote_priority",
+                f64::from(self.single_quote_priority),
+            ),
+            ("no_quote_priority", f64::from(self.no_quote_priority)),
+            ("tie_break_ratio", self.tie_break_ratio),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect();
+
+        let mut penalties: Vec<(u8, f64)> = self
+            .delimiter_penalties
+            .iter()
+            .map(|(&delimiter, &penalty)| (delimiter, penalty))
+            .collect();
+        penalties.sort_by_key(|&(delimiter, _)| delimiter);
+        parameters.extend(penalties.into_iter().map(|(delimiter, penalty)| {
+            (
+                format!("delimiter_penalty.{}", delimiter_name(delimiter)),
+                penalty,
+            )
+        }));
+
+        let mut priorities: Vec<(u8, u8)> = self
+            .delimiter_priorities
+            .iter()
+            .map(|(&delimiter, &priority)| (delimiter, priority))
+            .collect();
+        priorities.sort_by_key(|&(delimiter, _)| delimiter);
+        parameters.extend(priorities.into_iter().map(|(delimiter, priority)| {
+            (
+                format!("delimiter_priority.{}", delimiter_name(delimiter)),
+                f64::from(priority),
+            )
+        }));
+
+        parameters
+    }
+
+    /// Set one setting by its key (see [`parameters`](Self::parameters)).
+    ///
+    /// Row, field and priority settings must be whole numbers, the row and
+    /// field thresholds at least 1, and `tie_break_ratio` in (0, 1].
+    pub fn set_parameter(&mut self, key: &str, value: f64) -> Result<()> {
+        if !value.is_finite() || value < 0.0 {
+            return Err(SnifferError::InvalidConfig(format!(
+                "scoring parameter {key} must be a non-negative number, got {value}"
+            )));
+        }
+        match key {
+            "uniformity_weight" => self.uniformity_weight = value,
+            "type_weight" => self.type_weight = value,
+            "pattern_weight" => self.pattern_weight = value,
+            "row_bonus" => self.row_bonus = value,
+            "row_bonus_rows" => self.row_bonus_rows = threshold(key, value)?,
+            "field_bonus" => self.field_bonus = value,
+            "field_bonus_fields" => self.field_bonus_fields = threshold(key, value)?,
+            "single_field_penalty" => self.single_field_penalty = value,
+            "wide_table_penalty" => self.wide_table_penalty = value,
+            "very_wide_table_penalty" => self.very_wide_table_penalty = value,
+            "tiny_sample_penalty" => self.tiny_sample_penalty = value,
+            "small_sample_penalty" => self.small_sample_penalty = value,
+            "hash_relaxed_penalty" => self.hash_relaxed_penalty = value,
+            "unknown_delimiter_penalty" => self.unknown_delimiter_penalty = value,
+            "custom_delimiter_penalty" => self.custom_delimiter_penalty = value,
+            "section_sign_penalty" => self.section_sign_penalty = value,
+            "unicode_separator_penalty" => self.unicode_separator_penalty = value,
+            "section_sign_priority" => self.section_sign_priority = whole_number(key, value)?,
+            "double_quote_priority" => self.double_quote_priority = whole_number(key, value)?,
+            "single_quote_priority" => self.single_quote_priority = whole_number(key, value)?,
+            "no_quote_priority" => self.no_quote_priority = whole_number(key, value)?,
+            "tie_break_ratio" => {
+                if value == 0.0 || value > 1.0 {
+                    return Err(SnifferError::InvalidConfig(format!(
+                        "scoring parameter {key} must be in (0, 1], got {value}"
+                    )));
+                }
+                self.tie_break_ratio = value;
+            }
+            _ => {
+                if let Some(name) = key.strip_prefix("delimiter_penalty.") {
+                    self.delimiter_penalties
+                        .insert(parse_delimiter_name(name)?, value);
+                } else if let Some(name) = key.strip_prefix("delimiter_priority.") {
+                    self.delimiter_priorities
+                        .insert(parse_delimiter_name(name)?, whole_number(key, value)?);
+                } else {
+                    return Err(SnifferError::InvalidConfig(format!(
+                        "unknown scoring parameter: {key}"
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The character of a multi-character separator with the highest
+    /// delimiter priority, whose penalty and priority the separator takes.
+    pub(crate) fn sequence_delimiter(&self, bytes: &[u8]) -> Option<u8> {
+        bytes
+            .iter()
+            .copied()
+            .max_by_key(|&b| self.delimiter_priority(b))
+    }
+}
+
+impl fmt::Display for ScoringConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, value) in self.parameters() {
+            writeln!(f, "{key} = {value}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ScoringConfig {
+    type Err = SnifferError;
+
+    /// Parse `key = value` lines on top of the default configuration.
+    fn from_str(s: &str) -> Result<Self> {
+        let mut config = Self::default();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .ok_or_else(|| {
+                    SnifferError::InvalidConfig(format!(
+                        "line {}: expected `key = value`, got {line:?}",
+                        i + 1
+                    ))
+                })?;
+            let value: f64 = value.parse().map_err(|_| {
+                SnifferError::InvalidConfig(format!(
+                    "line {}: invalid number for {key}: {value:?}",
+                    i + 1
+                ))
+            })?;
+            config.set_parameter(key, value)?;
+        }
+        Ok(config)
+    }
+}
+
+/// The configuration-key name of a delimiter.
+fn delimiter_name(delimiter: u8) -> String {
+    DELIMITER_NAMES
+        .iter()
+        .find(|&&(b, _)| b == delimiter)
+        .map_or_else(
+            || format!("0x{delimiter:02x}"),
+            |&(_, name)| name.to_string(),
+        )
+}
+
+/// Parse a delimiter name from a configuration key: a name from
+/// `DELIMITER_NAMES` or `0xNN`.
+fn parse_delimiter_name(name: &str) -> Result<u8> {
+    if let Some(&(delimiter, _)) = DELIMITER_NAMES.iter().find(|&&(_, n)| n == name) {
+        return Ok(delimiter);
+    }
+    name.strip_prefix("0x")
+        .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        .ok_or_else(|| SnifferError::InvalidConfig(format!("unknown delimiter name: {name}")))
+}
+
+/// Convert a configuration value to a whole number type.
+fn whole_number<T: TryFrom<u64>>(key: &str, value: f64) -> Result<T> {
+    if value.fract() == 0.0
+        && let Ok(n) = T::try_from(value as u64)
+    {
+        return Ok(n);
+    }
+    Err(SnifferError::InvalidConfig(format!(
+        "scoring parameter {key} must be a whole number in range, got {value}"
+    )))
+}
+
+/// Convert a configuration value to a row or field threshold of at least 1.
+fn threshold(key: &str, value: f64) -> Result<usize> {
+    match whole_number(key, value)? {
+        0 => Err(SnifferError::InvalidConfig(format!(
+            "scoring parameter {key} must be at least 1, got {value}"
+        ))),
+        n => Ok(n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_round_trip() {
+        let mut config = ScoringConfig {
+            type_weight: 0.123_456_789,
+            ..ScoringConfig::default()
+        };
+        config.delimiter_priorities.insert(b';', 11);
+        config.delimiter_penalties.insert(b'!', 0.5);
+
+        let text = config.to_string();
+        assert!(text.contains("delimiter_priority.semicolon = 11\n"));
+        assert!(text.contains("delimiter_penalty.0x21 = 0.5\n"));
+        assert_eq!(text.parse::<ScoringConfig>().unwrap(), config);
+    }
+
+    #[test]
+    fn test_config_parse() {
+        let config: ScoringConfig =
+            "# tuned\n\ntie_break_ratio = 0.9\ndelimiter_penalty.tab = 0.95\n"
+                .parse()
+                .unwrap();
+        assert_eq!(config.tie_break_ratio, 0.9);
+        assert_eq!(config.delimiter_penalties[&b'\t'], 0.95);
+        assert_eq!(config.type_weight, ScoringConfig::default().type_weight);
+
+        for bad in [
+            "bogus = 1",
+            "type_weight 0.3",
+            "type_weight = high",
+            "row_bonus_rows = 2.5",
+            "row_bonus_rows = 0",
+            "field_bonus_fields = 0",
+            "tie_break_ratio = 0",
+            "tie_break_ratio = 1.5",
+            "delimiter_priority.comma = 300",
+            "delimiter_penalty.star = 1",
+        ] {
+            assert!(
+                matches!(
+                    bad.parse::<ScoringConfig>(),
+                    Err(SnifferError::InvalidConfig(_))
+                ),
+                "{bad}"
+            );
+        }
+    }
+}
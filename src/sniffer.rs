@@ -4,23 +4,41 @@
 
 use std::borrow::Cow;
 use std::fs::File;
-use std::io::{Read, Seek};
+use std::io::Read;
+use std::ops::Range;
 use std::path::Path;
 
-use crate::encoding::{detect_and_transcode, detect_encoding, skip_bom};
+use crate::encoding::{
+    EncodingInfo, detect_and_transcode, detect_encoding, skip_bom, transcode_with,
+};
 use crate::error::{Result, SnifferError};
 use crate::field_type::Type;
-use crate::metadata::{Dialect, Header, Metadata, Quote};
+use crate::lexicon;
+use crate::metadata::{
+    Dialect, FixedWidth, FixedWidthColumn, Header, HeaderVote, Layout, Metadata, Quote, Separator,
+};
 use crate::sample::{DatePreference, SampleSize};
+use crate::scoring::{DEFAULT_SCORING, ScoringConfig};
+use crate::stream::SniffState;
+use crate::tum::comment::{comment_byte, detect_comment_marker, is_comment_line};
+use crate::tum::fixed_width;
 use crate::tum::potential_dialects::{
-    PotentialDialect, detect_line_terminator, generate_dialects_with_terminator,
+    LineTerminator, PotentialDialect, QUOTES, detect_line_terminator, detect_line_terminator_style,
+    generate_dialects_for_delimiters, generate_dialects_with_terminator,
+};
+use crate::tum::score::{
+    DialectScore, dialect_confidence, explain_candidates, find_best_dialect, rank_candidates,
+    score_all_dialects_with_best_table, score_fixed_width_table,
+};
+use crate::tum::separator::{
+    choose_placeholder, detect_multi_char_separators, detect_unicode_separators,
+    detect_whitespace_runs, translate_separator,
 };
-use crate::tum::score::{DialectScore, find_best_dialect, score_all_dialects_with_best_table};
 use crate::tum::table::{Table, parse_table};
 use crate::tum::type_detection::infer_column_types;
 
-/// Maximum buffer size for `SampleSize::Records` mode (100 MB).
-const MAX_RECORDS_BYTES: usize = 100 * 1024 * 1024;
+/// Chunk size for reading samples from a reader (64 KB).
+pub(crate) const READ_CHUNK_SIZE: usize = 64 * 1024;
 
 /// CSV dialect sniffer using the Table Uniformity Method.
 ///
@@ -46,6 +64,20 @@ pub struct Sniffer {
     forced_delimiter: Option<u8>,
     /// Optional forced quote character.
     forced_quote: Option<Quote>,
+    /// Optional forced source encoding.
+    forced_encoding: Option<&'static encoding_rs::Encoding>,
+    /// Whether to record a per-candidate scoring trace.
+    explain: bool,
+    /// Optional forced header presence.
+    forced_header: Option<bool>,
+    /// Optional forced number of preamble rows.
+    forced_preamble_rows: Option<usize>,
+    /// Optional forced comment character.
+    forced_comment: Option<u8>,
+    /// Optional candidate delimiters replacing the built-in set.
+    candidate_delimiters: Option<Vec<u8>>,
+    /// Optional scoring weights replacing the built-in ones.
+    scoring: Option<ScoringConfig>,
 }
 
 impl Default for Sniffer {
@@ -62,6 +94,13 @@ impl Sniffer {
             date_preference: DatePreference::MdyFormat,
             forced_delimiter: None,
             forced_quote: None,
+            forced_encoding: None,
+            explain: false,
+            forced_header: None,
+            forced_preamble_rows: None,
+            forced_comment: None,
+            candidate_delimiters: None,
+            scoring: None,
         }
     }
 
@@ -89,6 +128,76 @@ impl Sniffer {
         self
     }
 
+    /// Force the source encoding (skip encoding detection).
+    ///
+    /// Sniffing fails with `SnifferError::EncodingMismatch` if more than 1% of
+    /// the decoded characters come from malformed sequences.
+    pub fn encoding(&mut self, encoding: &'static encoding_rs::Encoding) -> &mut Self {
+        self.forced_encoding = Some(encoding);
+        self
+    }
+
+    /// Restrict delimiter detection to the given candidates (replacing the
+    /// built-in set). Candidates outside the built-in set are scored with a
+    /// neutral prior instead of the unknown-delimiter penalty.
+    ///
+    /// Ignored when a delimiter is forced with [`delimiter`](Self::delimiter).
+    pub fn candidate_delimiters(&mut self, delimiters: &[u8]) -> &mut Self {
+        let mut candidates = Vec::with_capacity(delimiters.len());
+        for &delimiter in delimiters {
+            if !candidates.contains(&delimiter) {
+                candidates.push(delimiter);
+            }
+        }
+        self.candidate_delimiters = Some(candidates);
+        self
+    }
+
+    /// Force whether the first row after the preamble is a header (skip
+    /// header detection).
+    pub fn header(&mut self, has_header_row: bool) -> &mut Self {
+        self.forced_header = Some(has_header_row);
+        self
+    }
+
+    /// Force the number of preamble lines before the header or data (skip
+    /// comment and structural preamble detection). Exactly this many lines are
+    /// removed before dialect scoring.
+    pub fn preamble_rows(&mut self, preamble_rows: usize) -> &mut Self {
+        self.forced_preamble_rows = Some(preamble_rows);
+        self
+    }
+
+    /// Force the comment character (skip comment marker detection).
+    pub fn comment(&mut self, comment: u8) -> &mut Self {
+        self.forced_comment = Some(comment);
+        self
+    }
+
+    /// Record every bonus and penalty applied to each candidate dialect in
+    /// `Metadata::explanation`.
+    pub fn explain(&mut self, explain: bool) -> &mut Self {
+        self.explain = explain;
+        self
+    }
+
+    /// Replace the weights, delimiter penalties and tie-break priorities
+    /// used to score candidate dialects.
+    pub fn scoring(&mut self, scoring: ScoringConfig) -> &mut Self {
+        self.scoring = Some(scoring);
+        self
+    }
+
+    /// The scoring configuration in effect.
+    fn scoring_config(&self) -> &ScoringConfig {
+        self.scoring.as_ref().unwrap_or(&DEFAULT_SCORING)
+    }
+
+    /// The configured sample size.
+    pub(crate) fn get_sample_size(&self) -> SampleSize {
+        self.sample_size
+    }
+
     /// Sniff a CSV file at the given path.
     pub fn sniff_path<P: AsRef<Path>>(&mut self, path: P) -> Result<Metadata> {
         let file = File::open(path.as_ref())?;
@@ -97,24 +206,70 @@ impl Sniffer {
     }
 
     /// Sniff CSV data from a reader.
-    pub fn sniff_reader<R: Read + Seek>(&mut self, reader: R) -> Result<Metadata> {
-        let data = self.read_sample(reader)?;
-
-        if data.is_empty() {
-            return Err(SnifferError::EmptyData);
-        }
+    ///
+    /// Only as many bytes as the sample size requires are read (plus one to
+    /// check for the end of input), so the reader need not be seekable.
+    pub fn sniff_reader<R: Read>(&mut self, reader: R) -> Result<Metadata> {
+        let (state, reached_eof) = self.read_sample(reader)?;
+        state.finish_with_eof(reached_eof)
+    }
 
-        self.sniff_bytes(&data)
+    /// Start an incremental sniff that is fed chunks of input.
+    ///
+    /// See [`SniffState`] for sockets, pipes and other push-based sources.
+    pub fn sniff_state(&self) -> SniffState {
+        SniffState::new(self.clone())
     }
 
     /// Sniff CSV data from bytes.
     pub fn sniff_bytes(&self, data: &[u8]) -> Result<Metadata> {
+        self.sniff_sample(data, true)
+    }
+
+    /// Sniff a file at the given path that may be delimited or fixed-width.
+    pub fn sniff_layout_path<P: AsRef<Path>>(&mut self, path: P) -> Result<Layout> {
+        let file = File::open(path.as_ref())?;
+        self.sniff_layout_reader(std::io::BufReader::new(file))
+    }
+
+    /// Sniff data from a reader that may be delimited or fixed-width.
+    pub fn sniff_layout_reader<R: Read>(&mut self, reader: R) -> Result<Layout> {
+        let (state, reached_eof) = self.read_sample(reader)?;
+        state.finish_layout_with_eof(reached_eof)
+    }
+
+    /// Sniff bytes that may be delimited or fixed-width.
+    ///
+    /// Aligned columns separated by positions that are blank on every line
+    /// are scored like a delimited table. `Layout::FixedWidth` is returned
+    /// when that score beats the best delimited dialect, e.g. when values
+    /// contain spaces and splitting on whitespace would give ragged rows.
+    pub fn sniff_layout_bytes(&self, data: &[u8]) -> Result<Layout> {
+        self.sniff_layout_sample(data, true)
+    }
+
+    /// Sniff a sample of CSV data.
+    ///
+    /// `reached_eof` is false when the sample was cut short of the end of the
+    /// input, in which case the last rows cannot be treated as a footer.
+    pub(crate) fn sniff_sample(&self, data: &[u8], reached_eof: bool) -> Result<Metadata> {
         if data.is_empty() {
             return Err(SnifferError::EmptyData);
         }
+        if self.forced_delimiter.is_none()
+            && self
+                .candidate_delimiters
+                .as_ref()
+                .is_some_and(Vec::is_empty)
+        {
+            return Err(SnifferError::InvalidConfig(
+                "candidate delimiter set is empty".to_string(),
+            ));
+        }
 
         // Detect encoding and transcode to UTF-8 if necessary
-        let (transcoded_data, was_transcoded) = detect_and_transcode(data);
+        let (transcoded_data, source_encoding) = self.transcode(data)?;
+        let was_transcoded = source_encoding.encoding != encoding_rs::UTF_8;
         let data = &transcoded_data[..];
 
         // Detect encoding info (for metadata)
@@ -123,16 +278,20 @@ impl Sniffer {
 
         // Skip BOM
         let data = skip_bom(data);
+        let sample = data;
 
-        // Skip comment/preamble lines (lines starting with #)
-        let (comment_preamble_rows, data) = skip_preamble(data);
+        // Detect the comment marker and skip comment/preamble lines
+        let (comment, comment_preamble_rows, data) = self.strip_comment_preamble(data);
 
         // Detect line terminator first to reduce search space
         let line_terminator = detect_line_terminator(data);
 
         // Generate potential dialects
-        let dialects = self.forced_delimiter.map_or_else(
-            || generate_dialects_with_terminator(line_terminator),
+        let mut dialects: Vec<PotentialDialect> = self.forced_delimiter.map_or_else(
+            || match &self.candidate_delimiters {
+                Some(candidates) => generate_dialects_for_delimiters(candidates, line_terminator),
+                None => generate_dialects_with_terminator(line_terminator),
+            },
             |delim| {
                 // If delimiter is forced, only test that delimiter with different quotes
                 let quotes = if let Some(q) = self.forced_quote {
@@ -147,6 +306,12 @@ impl Sniffer {
                     .collect()
             },
         );
+        if comment.is_some() {
+            dialects = dialects
+                .into_iter()
+                .map(|d| d.with_comment(comment))
+                .collect();
+        }
         // Determine max rows for scoring
         let max_rows = match self.sample_size {
             SampleSize::Records(n) => n,
@@ -154,89 +319,229 @@ impl Sniffer {
         };
 
         // Score all dialects and get the best table (avoids re-parsing)
-        let (scores, best_table) = score_all_dialects_with_best_table(data, &dialects, max_rows);
+        let (mut scores, best_table) = score_all_dialects_with_best_table(
+            data,
+            &dialects,
+            max_rows,
+            self.scoring_config(),
+            self.explain,
+        );
+
+        // Score multi-byte separators found by character and n-gram frequency
+        // alongside them
+        let separator_candidates =
+            if self.forced_delimiter.is_none() && self.candidate_delimiters.is_none() {
+                score_multi_byte_separators(
+                    data,
+                    line_terminator,
+                    comment,
+                    max_rows,
+                    self.scoring_config(),
+                    self.explain,
+                )
+            } else {
+                Vec::new()
+            };
+        if !separator_candidates.is_empty() {
+            scores.extend(separator_candidates.iter().map(|(score, _)| score.clone()));
+            scores.sort_by(|a, b| {
+                b.gamma
+                    .partial_cmp(&a.gamma)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+        }
 
         // Find the best dialect
-        let best = find_best_dialect(&scores)
+        let best = find_best_dialect(&scores, self.scoring_config())
             .ok_or_else(|| SnifferError::NoDialectDetected("No valid dialect found".to_string()))?;
 
         // Detect structural preamble using the already-parsed table
-        let table_for_preamble =
-            best_table.unwrap_or_else(|| parse_table(data, &best.dialect, max_rows));
-        let structural_preamble = detect_structural_preamble(&table_for_preamble);
+        let separator_table = separator_candidates
+            .into_iter()
+            .find(|(score, _)| score.dialect == best.dialect)
+            .map(|(_, table)| table);
+        let table_for_preamble = separator_table
+            .or(best_table)
+            .unwrap_or_else(|| parse_table(data, &best.dialect, max_rows));
+        let structural_preamble = if self.forced_preamble_rows.is_some() {
+            0
+        } else {
+            detect_structural_preamble(&table_for_preamble)
+        };
+
+        // Detect footer rows only when the table extends to the end of the input
+        let table_complete =
+            reached_eof && (max_rows == 0 || table_for_preamble.num_rows() < max_rows);
+        let footer_rows = if table_complete {
+            detect_footer(&table_for_preamble, structural_preamble)
+        } else {
+            0
+        };
 
         // Total preamble = comment rows + structural rows
         let total_preamble_rows = comment_preamble_rows + structural_preamble;
 
         // Build metadata from the best dialect, reusing the already-parsed table
-        // Pass structural_preamble for table row indexing (since comment rows are already skipped from data)
+        // Pass the data row range for table row indexing (since comment rows are already skipped from data)
         // Pass total_preamble_rows for Header metadata (to report true preamble count in original file)
-        self.build_metadata(
+        let data_rows = structural_preamble..table_for_preamble.num_rows() - footer_rows;
+        let mut metadata = self.build_metadata(
             best,
             is_utf8,
-            structural_preamble,
+            data_rows,
             total_preamble_rows,
             &table_for_preamble,
             data,
-        )
+        )?;
+        metadata.encoding = source_encoding;
+        // The structural preamble counts parsed records, which may span
+        // several lines or be separated by blank lines
+        let preamble_len =
+            sample.len() - data.len() + records_len(data, &best.dialect, structural_preamble);
+        metadata.preamble = sample[..preamble_len].to_vec();
+        metadata.dialect.header.num_preamble_lines = count_lines(&metadata.preamble);
+        metadata.candidates = rank_candidates(&scores, best);
+        metadata.confidence = dialect_confidence(&scores, best, self.scoring_config());
+        if self.explain {
+            metadata.explanation = Some(explain_candidates(&scores, best, self.scoring_config()));
+        }
+
+        Ok(metadata)
     }
 
-    /// Read a sample of data from the reader based on `sample_size` settings.
-    fn read_sample<R: Read + Seek>(&self, mut reader: R) -> Result<Vec<u8>> {
-        match self.sample_size {
-            SampleSize::Bytes(n) => {
-                let mut buffer = vec![0u8; n];
-                let bytes_read = reader.read(&mut buffer)?;
-                buffer.truncate(bytes_read);
-                Ok(buffer)
+    /// Decode `data` to UTF-8 from the forced or detected encoding.
+    fn transcode<'a>(&self, data: &'a [u8]) -> Result<(Cow<'a, [u8]>, EncodingInfo)> {
+        match self.forced_encoding {
+            Some(encoding) => transcode_with(data, encoding),
+            None => Ok(detect_and_transcode(data)),
+        }
+    }
+
+    /// Detect the comment marker from lines anywhere in `data` and skip the
+    /// leading comment/preamble lines (lines starting with the forced or
+    /// detected marker, or with `#` when there is none), or exactly the forced
+    /// number of preamble lines.
+    ///
+    /// Returns the comment character, the number of lines skipped and the
+    /// remaining data.
+    fn strip_comment_preamble<'a>(&self, data: &'a [u8]) -> (Option<u8>, usize, &'a [u8]) {
+        let (comment_marker, comment) = match self.forced_comment {
+            Some(ref c) => (Some(std::slice::from_ref(c)), Some(*c)),
+            None => {
+                let marker = detect_comment_marker(data);
+                (marker, marker.and_then(|marker| comment_byte(data, marker)))
             }
-            SampleSize::All => {
-                const MAX_BYTES: u64 = 1024 * 1024 * 1024; // 1 GB hard cap
-                let mut buffer = Vec::new();
-                (&mut reader).take(MAX_BYTES).read_to_end(&mut buffer)?;
-                if buffer.len() as u64 == MAX_BYTES {
-                    let mut probe = [0u8; 1];
-                    if reader.read(&mut probe)? > 0 {
-                        eprintln!(
-                            "warning: input exceeds 1 GB; sniffing on truncated sample — results may be inaccurate"
-                        );
-                    }
-                }
-                Ok(buffer)
+        };
+
+        match self.forced_preamble_rows {
+            Some(rows) => (comment, rows, skip_lines(data, rows)),
+            None => {
+                let (rows, data) = skip_preamble(data, comment_marker.unwrap_or(b"#"));
+                (comment, rows, data)
             }
-            SampleSize::Records(n) => {
-                // For records, we read enough to capture n records
-                // Estimate ~1KB per record as a starting point, with a minimum
-                let estimated_size = n.saturating_mul(1024).clamp(8192, MAX_RECORDS_BYTES);
-                let mut buffer = vec![0u8; estimated_size];
-                let bytes_read = reader.read(&mut buffer)?;
-                buffer.truncate(bytes_read);
-
-                // If we need more data, keep reading
-                if bytes_read == estimated_size {
-                    // Count newlines to see if we have enough records
-                    let newlines = bytecount::count(&buffer, b'\n');
-                    if newlines < n {
-                        // Read more data
-                        let additional = (n - newlines).saturating_mul(2048).min(MAX_RECORDS_BYTES);
-                        let mut more = vec![0u8; additional];
-                        let more_read = reader.read(&mut more)?;
-                        more.truncate(more_read);
-                        buffer.extend(more);
-                    }
-                }
+        }
+    }
 
-                if buffer.len() >= MAX_RECORDS_BYTES {
-                    let mut probe = [0u8; 1];
-                    if reader.read(&mut probe)? > 0 {
-                        eprintln!(
-                            "warning: Records sample capped at 100 MB; \
-                             sniff result may be approximate for very large inputs"
-                        );
-                    }
-                }
+    /// Sniff a sample for either a delimited dialect or a fixed-width layout.
+    ///
+    /// The fixed-width layout is chosen when its gamma (uniformity, type and
+    /// pattern scores of the sliced columns) beats the best delimited dialect.
+    pub(crate) fn sniff_layout_sample(&self, data: &[u8], reached_eof: bool) -> Result<Layout> {
+        let delimited = self.sniff_sample(data, reached_eof);
+        let delimited_gamma = delimited
+            .as_ref()
+            .ok()
+            .and_then(|metadata| metadata.candidates.first())
+            .map_or(0.0, |candidate| candidate.gamma);
+
+        match self.detect_fixed_width(data)? {
+            Some(mut fixed_width) if fixed_width.gamma > delimited_gamma => {
+                fixed_width.delimited_gamma = delimited_gamma;
+                Ok(Layout::FixedWidth(fixed_width))
+            }
+            _ => delimited.map(Layout::Delimited),
+        }
+    }
+
+    /// Find a fixed-width layout in `data` from aligned blank positions.
+    fn detect_fixed_width(&self, data: &[u8]) -> Result<Option<FixedWidth>> {
+        if data.is_empty() || self.forced_delimiter.is_some() {
+            return Ok(None);
+        }
+        let (transcoded_data, encoding) = self.transcode(data)?;
+        let data = skip_bom(&transcoded_data);
+        let (_, preamble_rows, data) = self.strip_comment_preamble(data);
+
+        let max_rows = match self.sample_size {
+            SampleSize::Records(n) => n,
+            SampleSize::Bytes(_) | SampleSize::All => 0,
+        };
+        let Some(lines) = fixed_width::layout_lines(data, max_rows) else {
+            return Ok(None);
+        };
+        let Some(columns) = fixed_width::detect_columns(&lines) else {
+            return Ok(None);
+        };
+        let table = fixed_width::slice_table(&lines, &columns);
+        let score = score_fixed_width_table(&table, self.scoring_config());
+
+        let line_terminator = detect_line_terminator_style(data, Quote::None, None);
+        let has_header_row = self
+            .forced_header
+            .unwrap_or_else(|| detect_header(&table, &score.dialect, preamble_rows).has_header_row);
+        let types = if has_header_row {
+            let mut body = Table::new();
+            body.rows = table.rows[1..].to_vec();
+            body.field_counts = table.field_counts[1..].to_vec();
+            body.update_modal_field_count();
+            infer_column_types(&body)
+        } else {
+            infer_column_types(&table)
+        };
 
-                Ok(buffer)
+        let columns = columns
+            .into_iter()
+            .zip(types)
+            .enumerate()
+            .map(|(i, (range, field_type))| FixedWidthColumn {
+                name: if has_header_row {
+                    table.rows[0][i].clone()
+                } else {
+                    format!("field_{}", i + 1)
+                },
+                start: range.start,
+                end: range.end,
+                field_type,
+            })
+            .collect();
+
+        Ok(Some(FixedWidth {
+            columns,
+            has_header_row,
+            num_preamble_rows: preamble_rows,
+            line_terminator,
+            encoding,
+            gamma: score.gamma,
+            delimited_gamma: 0.0,
+        }))
+    }
+
+    /// Read a sample of data from the reader based on `sample_size` settings.
+    ///
+    /// Reads in chunks until the sample is satisfied, then probes one more
+    /// byte to find out whether the sample extends to the end of the input.
+    fn read_sample<R: Read>(&self, mut reader: R) -> Result<(SniffState, bool)> {
+        let mut state = self.sniff_state();
+        let mut chunk = vec![0u8; READ_CHUNK_SIZE];
+        loop {
+            let want = state.next_read_len(READ_CHUNK_SIZE);
+            let bytes_read = match reader.read(&mut chunk[..want]) {
+                Ok(n) => n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            };
+            if let Some(reached_eof) = state.feed_read(&chunk[..bytes_read]) {
+                return Ok((state, reached_eof));
             }
         }
     }
@@ -244,7 +549,7 @@ impl Sniffer {
     /// Build Metadata from the best scoring dialect.
     ///
     /// # Arguments
-    /// * `structural_preamble` - Number of structural preamble rows in the table (for row indexing)
+    /// * `data_rows` - Table rows between the structural preamble and the footer (for row indexing)
     /// * `total_preamble_rows` - Total preamble rows including comments (for Header metadata)
     /// * `table` - Pre-parsed table to avoid redundant parsing
     /// * `data` - Raw data bytes for accurate avg_record_len calculation
@@ -252,7 +557,7 @@ impl Sniffer {
         &self,
         score: &DialectScore,
         is_utf8: bool,
-        structural_preamble: usize,
+        data_rows: Range<usize>,
         total_preamble_rows: usize,
         table: &Table,
         data: &[u8],
@@ -261,14 +566,15 @@ impl Sniffer {
             return Err(SnifferError::EmptyData);
         }
 
-        // Create a view of the table without structural preamble
+        // Create a view of the table without structural preamble or footer
         // (comment preamble rows are already stripped from data)
-        // Use Cow to avoid cloning in the common no-preamble case
+        // Use Cow to avoid cloning in the common no-preamble, no-footer case
+        let footer_rows = table.rows.len() - data_rows.end;
         let effective_table: Cow<'_, Table> =
-            if structural_preamble > 0 && table.rows.len() > structural_preamble {
+            if data_rows.len() < table.rows.len() && !data_rows.is_empty() {
                 let mut et = Table::new();
-                et.rows = table.rows[structural_preamble..].to_vec();
-                et.field_counts = table.field_counts[structural_preamble..].to_vec();
+                et.rows = table.rows[data_rows.clone()].to_vec();
+                et.field_counts = table.field_counts[data_rows].to_vec();
                 et.update_modal_field_count();
                 Cow::Owned(et)
             } else {
@@ -276,10 +582,30 @@ impl Sniffer {
             };
 
         // Detect header on the effective table (pass total_preamble_rows for Header metadata)
-        let header = detect_header(&effective_table, &score.dialect, total_preamble_rows);
+        let mut header = match self.forced_header {
+            Some(has_header_row) => Header::new(has_header_row, total_preamble_rows),
+            None => detect_header(&effective_table, &score.dialect, total_preamble_rows),
+        };
+        header.num_footer_rows = footer_rows;
 
-        // Get field names from the effective table (first row after structural preamble)
-        let fields = if header.has_header_row && !effective_table.rows.is_empty() {
+        // Extend the header to stacked grouping/units rows
+        let header_rows = match self.forced_header {
+            Some(has_header_row) => HeaderRows {
+                count: usize::from(has_header_row),
+                grouping: 0,
+            },
+            None => detect_header_rows(&effective_table.rows, header.has_header_row),
+        };
+        header.has_header_row = header_rows.count > 0;
+        header.num_header_rows = header_rows.count;
+
+        // Get field names from the effective table (header rows after structural preamble)
+        let fields = if header_rows.count > 1 {
+            combine_header_names(
+                &effective_table.rows[..header_rows.count],
+                header_rows.grouping,
+            )
+        } else if header.has_header_row && !effective_table.rows.is_empty() {
             effective_table.rows[0].clone()
         } else {
             // Generate field names
@@ -288,11 +614,12 @@ impl Sniffer {
                 .collect()
         };
 
-        // Skip header row for type inference if present
-        let data_table = if header.has_header_row && effective_table.rows.len() > 1 {
+        // Skip header rows for type inference if present
+        let data_table = if header.has_header_row && effective_table.rows.len() > header_rows.count
+        {
             let mut dt = crate::tum::table::Table::new();
-            dt.rows = effective_table.rows[1..].to_vec();
-            dt.field_counts = effective_table.field_counts[1..].to_vec();
+            dt.rows = effective_table.rows[header_rows.count..].to_vec();
+            dt.field_counts = effective_table.field_counts[header_rows.count..].to_vec();
             dt.update_modal_field_count();
             dt
         } else {
@@ -305,10 +632,19 @@ impl Sniffer {
         // Build dialect
         let dialect = Dialect {
             delimiter: score.dialect.delimiter,
+            separator: score.dialect.separator.clone(),
             header,
             quote: score.dialect.quote,
             flexible: !score.is_uniform,
             is_utf8,
+            escape: score.dialect.escape,
+            double_quote: score.dialect.escape.is_none(),
+            comment: score.dialect.comment,
+            line_terminator: detect_line_terminator_style(
+                data,
+                score.dialect.quote,
+                score.dialect.escape,
+            ),
         };
 
         // Calculate average record length from the raw data
@@ -320,10 +656,70 @@ impl Sniffer {
             num_fields: score.num_fields,
             fields,
             types,
+            encoding: EncodingInfo::default(),
+            candidates: Vec::new(),
+            confidence: 1.0,
+            explanation: None,
+            preamble: Vec::new(),
+            date_preference: self.date_preference,
         })
     }
 }
 
+/// Score the multi-byte separators found in `data`: Unicode characters found
+/// by character frequency, multi-character sequences found by n-gram
+/// frequency and, for data padded with blanks, whitespace runs.
+///
+/// Each search starts with a linear check (non-ASCII bytes, pairs of sequence
+/// characters, padded lines), so plain ASCII CSV adds no scoring passes.
+///
+/// Each separator is translated to a stand-in byte absent from the data and
+/// scored once per quote character (translation skips quoted fields). Returns
+/// each score with its parsed table.
+fn score_multi_byte_separators(
+    data: &[u8],
+    line_terminator: LineTerminator,
+    comment: Option<u8>,
+    max_rows: usize,
+    config: &ScoringConfig,
+    explain: bool,
+) -> Vec<(DialectScore, Table)> {
+    let separators: Vec<Separator> = detect_unicode_separators(data)
+        .into_iter()
+        .map(Separator::Char)
+        .chain(
+            detect_multi_char_separators(data)
+                .into_iter()
+                .map(Separator::Sequence),
+        )
+        .chain(detect_whitespace_runs(data).then_some(Separator::Whitespace))
+        .collect();
+    let Some(placeholder) = separators.first().and_then(|_| choose_placeholder(data)) else {
+        return Vec::new();
+    };
+
+    let mut scored = Vec::with_capacity(separators.len() * QUOTES.len());
+    for separator in separators {
+        for &quote in QUOTES {
+            let translated = translate_separator(data, &separator, placeholder, quote, None);
+            let dialect = PotentialDialect::new(placeholder, quote, line_terminator)
+                .with_separator(separator.clone())
+                .with_comment(comment);
+            let (mut scores, table) = score_all_dialects_with_best_table(
+                &translated,
+                &[dialect],
+                max_rows,
+                config,
+                explain,
+            );
+            if let (Some(score), Some(table)) = (scores.pop(), table) {
+                scored.push((score, table));
+            }
+        }
+    }
+    scored
+}
+
 /// Detect if the first row (after preamble) is likely a header row.
 ///
 /// Optimized: Computes type counts in a single pass without allocating Vecs.
@@ -406,10 +802,317 @@ fn detect_header(
     }
     checks += 1;
 
+    // Check 5: Per-column type/length discrepancy votes, weighted by the
+    // share of non-abstaining columns that vote for a header
+    let column_types = body_column_types(table);
+    let column_votes = column_header_votes(table, &column_types);
+    let header_votes = column_votes
+        .iter()
+        .filter(|&&v| v == HeaderVote::Header)
+        .count();
+    let data_votes = column_votes
+        .iter()
+        .filter(|&&v| v == HeaderVote::Data)
+        .count();
+    if header_votes + data_votes > 0 {
+        header_score +=
+            COLUMN_VOTE_WEIGHT * header_votes as f64 / (header_votes + data_votes) as f64;
+        checks += 2;
+    }
+
+    // Check 6: In all-text tables, compare how much the first row looks like
+    // column names (lexicon words, identifier shapes) against the data rows
+    if column_types.contains(&Type::Text)
+        && column_types
+            .iter()
+            .all(|&t| t == Type::Text || t == Type::NULL)
+    {
+        let first_likelihood = lexicon::row_header_likelihood(first_row);
+        let body = &table.rows[1..table.rows.len().min(LEXICON_SAMPLE_ROWS + 1)];
+        let body_likelihood = body
+            .iter()
+            .map(|row| lexicon::row_header_likelihood(row))
+            .sum::<f64>()
+            / body.len() as f64;
+        let margin = (first_likelihood - body_likelihood) / LEXICON_FULL_MARGIN;
+        header_score += LEXICON_WEIGHT * margin.clamp(0.0, 1.0);
+        checks += 2;
+    }
+
     // Threshold for header detection
     let has_header = (header_score / checks as f64) > 0.4;
 
-    Header::new(has_header, preamble_rows)
+    let mut header = Header::new(has_header, preamble_rows);
+    header.column_votes = column_votes;
+    header
+}
+
+/// Weight of the per-column vote check, counted as two checks.
+const COLUMN_VOTE_WEIGHT: f64 = 2.0;
+
+/// Weight of the header-name likelihood check for all-text tables, counted as
+/// two checks.
+const LEXICON_WEIGHT: f64 = 2.0;
+
+/// Likelihood margin of the first row over the data rows that earns the full
+/// `LEXICON_WEIGHT`.
+const LEXICON_FULL_MARGIN: f64 = 0.5;
+
+/// Number of data rows compared against the first row for header likelihood.
+const LEXICON_SAMPLE_ROWS: usize = 20;
+
+/// Column types inferred from every row after the first.
+fn body_column_types(table: &Table) -> Vec<Type> {
+    let mut body = Table::new();
+    body.rows = table.rows[1..].to_vec();
+    body.field_counts = table.field_counts[1..].to_vec();
+    body.update_modal_field_count();
+    infer_column_types(&body)
+}
+
+/// Vote per column on whether the first row is a header, in the spirit of
+/// Python's `csv.Sniffer.has_header`.
+///
+/// Each first-row cell is compared with the column type inferred from the
+/// remaining rows:
+/// - Typed columns vote `Data` when the cell has the column's type, or a
+///   narrower type that also occurs below it (`7` in a Float column that
+///   holds `12`). Otherwise they vote `Header`, so `2019` above decimal values
+///   counts as a header.
+/// - Text columns whose values (at least two) all share one length vote
+///   `Header` when the cell's length differs (codes, IDs), `Data` otherwise.
+/// - Variable-length text columns, all-null columns and blank cells abstain.
+fn column_header_votes(table: &Table, column_types: &[Type]) -> Vec<HeaderVote> {
+    let Some((first_row, rest)) = table.rows.split_first() else {
+        return Vec::new();
+    };
+    if rest.is_empty() {
+        return vec![HeaderVote::Abstain; first_row.len()];
+    }
+
+    first_row
+        .iter()
+        .enumerate()
+        .map(|(col, cell)| {
+            let cell_type = crate::tum::type_detection::detect_cell_type(cell);
+            let column_type = column_types.get(col).copied().unwrap_or(Type::NULL);
+            if cell_type == Type::NULL || column_type == Type::NULL {
+                return HeaderVote::Abstain;
+            }
+
+            let values = rest.iter().filter_map(|row| row.get(col));
+            if column_type == Type::Text {
+                let lengths: Vec<usize> = values
+                    .filter(|v| !v.trim().is_empty())
+                    .map(|v| v.chars().count())
+                    .collect();
+                if lengths.len() < 2 {
+                    return HeaderVote::Abstain;
+                }
+                let len = lengths[0];
+                if lengths.iter().any(|&l| l != len) {
+                    return HeaderVote::Abstain;
+                }
+                return if cell.chars().count() == len {
+                    HeaderVote::Data
+                } else {
+                    HeaderVote::Header
+                };
+            }
+
+            let fits = cell_type == column_type
+                || (cell_type.merge(column_type) == column_type
+                    && values
+                        .map(|v| crate::tum::type_detection::detect_cell_type(v))
+                        .any(|t| t == cell_type));
+            if fits {
+                HeaderVote::Data
+            } else {
+                HeaderVote::Header
+            }
+        })
+        .collect()
+}
+
+/// Maximum number of stacked header rows.
+const MAX_HEADER_ROWS: usize = 3;
+
+/// Header rows found at the top of the effective table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct HeaderRows {
+    /// Total number of header rows (0 when there is no header).
+    count: usize,
+    /// Number of leading grouping rows whose blank cells continue the label
+    /// to their left (merged cells in the source spreadsheet).
+    grouping: usize,
+}
+
+/// Extend single-row header detection to stacked header rows.
+///
+/// Recognises a grouping row with merged-cell blanks above the column names,
+/// and a units row (e.g. `USD`, `kg`, `%`) below them. At most
+/// `MAX_HEADER_ROWS` rows are treated as header, and at least two data rows
+/// must remain below them.
+fn detect_header_rows(rows: &[Vec<String>], has_header_row: bool) -> HeaderRows {
+    let mut header_rows = HeaderRows {
+        count: usize::from(has_header_row),
+        grouping: 0,
+    };
+    if rows.len() < 4 {
+        return header_rows;
+    }
+
+    // Column types come from rows below any possible header row
+    let typed = typed_columns(&rows[MAX_HEADER_ROWS.min(rows.len() - 2)..]);
+
+    if is_grouping_row(&rows[0], &rows[1], &typed) {
+        header_rows = HeaderRows {
+            count: 2,
+            grouping: 1,
+        };
+    }
+    if header_rows.count == 0 {
+        return header_rows;
+    }
+
+    let next = header_rows.count;
+    if next < MAX_HEADER_ROWS && rows.len() >= next + 3 && is_label_row(&rows[next], &typed) {
+        header_rows.count += 1;
+    }
+
+    header_rows
+}
+
+/// Columns whose values (ignoring blanks and nulls) are at least 80% non-text.
+fn typed_columns(rows: &[Vec<String>]) -> Vec<bool> {
+    let num_cols = rows.iter().map(Vec::len).max().unwrap_or(0);
+    (0..num_cols)
+        .map(|col| {
+            let (typed, total) = rows
+                .iter()
+                .filter_map(|row| row.get(col))
+                .map(|cell| crate::tum::type_detection::detect_cell_type(cell))
+                .filter(|t| *t != Type::NULL)
+                .fold((0, 0), |(typed, total), t| {
+                    (typed + usize::from(t != Type::Text), total + 1)
+                });
+            total > 0 && typed as f64 >= total as f64 * 0.8
+        })
+        .collect()
+}
+
+fn is_blank(cell: &str) -> bool {
+    cell.trim().is_empty()
+}
+
+fn is_text(cell: &str) -> bool {
+    crate::tum::type_detection::detect_cell_type(cell) == Type::Text
+}
+
+/// Whether a row holds labels rather than data: every non-blank cell is text,
+/// and text sits in at least half of the typed columns (and at least two).
+fn is_label_row(row: &[String], typed: &[bool]) -> bool {
+    if !row.iter().filter(|c| !is_blank(c)).all(|c| is_text(c)) {
+        return false;
+    }
+    let typed_count = typed.iter().filter(|&&t| t).count();
+    let labelled = row
+        .iter()
+        .zip(typed)
+        .filter(|(cell, t)| **t && is_text(cell))
+        .count();
+    typed_count >= 2 && labelled * 2 >= typed_count
+}
+
+/// Whether `first` is a grouping row above the column names in `second`.
+///
+/// A grouping row has text labels separated by blanks that sit above
+/// non-blank column names (merged cells), and fewer labels than `second`.
+/// `second` must itself look like a label row, or repeat the same names under
+/// different groups (e.g. `2023,2024` under both `Revenue` and `Cost`).
+fn is_grouping_row(first: &[String], second: &[String], typed: &[bool]) -> bool {
+    if first.len() > second.len() || !first.iter().filter(|c| !is_blank(c)).all(|c| is_text(c)) {
+        return false;
+    }
+    let labels = first.iter().filter(|c| !is_blank(c)).count();
+    let names = second.iter().filter(|c| !is_blank(c)).count();
+    if labels == 0 || labels >= names {
+        return false;
+    }
+
+    // Group spans: each label covers the blanks to its right
+    let mut spans: Vec<(usize, usize)> = Vec::new();
+    for (i, cell) in first.iter().enumerate() {
+        if !is_blank(cell) {
+            spans.push((i, i + 1));
+        } else if let Some(span) = spans.last_mut()
+            && span.1 == i
+            && second.get(i).is_some_and(|c| !is_blank(c))
+        {
+            span.1 = i + 1;
+        }
+    }
+    let merged = spans.iter().filter(|(start, end)| end - start >= 2).count();
+    if merged == 0 {
+        return false;
+    }
+
+    if is_label_row(second, typed) {
+        return true;
+    }
+
+    // Names repeated across groups, but unique within each group
+    if merged < 2 {
+        return false;
+    }
+    let mut repeated = false;
+    let mut seen: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
+    for (group, &(start, end)) in spans.iter().enumerate() {
+        for cell in &second[start..end] {
+            match seen.insert(cell.trim(), group) {
+                Some(prev) if prev == group => return false,
+                Some(_) => repeated = true,
+                None => {}
+            }
+        }
+    }
+    repeated
+}
+
+/// Combine stacked header rows into one name per column, joined with `_`.
+///
+/// The first `grouping` rows are forward-filled across their blank cells.
+fn combine_header_names(rows: &[Vec<String>], grouping: usize) -> Vec<String> {
+    let num_cols = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let filled: Vec<Vec<&str>> = rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut last = "";
+            (0..num_cols)
+                .map(|col| {
+                    let cell = row.get(col).map_or("", |c| c.trim());
+                    if i < grouping && cell.is_empty() {
+                        last
+                    } else {
+                        last = cell;
+                        cell
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    (0..num_cols)
+        .map(|col| {
+            filled
+                .iter()
+                .map(|row| row[col])
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join("_")
+        })
+        .collect()
 }
 
 /// Calculate average record length from raw data.
@@ -449,27 +1152,22 @@ fn calculate_avg_record_len(data: &[u8], num_rows: usize) -> usize {
 
 /// Skip preamble/comment lines at the start of data.
 ///
-/// Detects lines starting with '#' at the beginning of the file and returns
-/// the number of preamble rows and a slice starting after the preamble.
-fn skip_preamble(data: &[u8]) -> (usize, &[u8]) {
+/// Detects lines starting with `marker` (after optional leading whitespace) at
+/// the beginning of the file and returns the number of preamble rows and a
+/// slice starting after the preamble.
+fn skip_preamble<'a>(data: &'a [u8], marker: &[u8]) -> (usize, &'a [u8]) {
     let mut preamble_rows = 0;
     let mut offset = 0;
 
     while offset < data.len() {
-        // Skip leading whitespace on the line
-        let mut line_start = offset;
-        while line_start < data.len() && (data[line_start] == b' ' || data[line_start] == b'\t') {
-            line_start += 1;
+        // Find end of line
+        let mut line_end = offset;
+        while line_end < data.len() && data[line_end] != b'\n' && data[line_end] != b'\r' {
+            line_end += 1;
         }
 
-        // Check if line starts with #
-        if line_start < data.len() && data[line_start] == b'#' {
-            // Find end of line
-            let mut line_end = line_start;
-            while line_end < data.len() && data[line_end] != b'\n' && data[line_end] != b'\r' {
-                line_end += 1;
-            }
-
+        // Check if line starts with the comment marker
+        if is_comment_line(&data[offset..line_end], marker) {
             // Skip line terminator
             if line_end < data.len() && data[line_end] == b'\r' {
                 line_end += 1;
@@ -489,6 +1187,74 @@ fn skip_preamble(data: &[u8]) -> (usize, &[u8]) {
     (preamble_rows, &data[offset..])
 }
 
+/// Skip the first `rows` lines of data, whatever they contain.
+///
+/// Lines may end with LF, CRLF or CR.
+fn skip_lines(data: &[u8], rows: usize) -> &[u8] {
+    let mut offset = 0;
+    for _ in 0..rows {
+        match data[offset..]
+            .iter()
+            .position(|&b| b == b'\n' || b == b'\r')
+        {
+            Some(pos) => {
+                offset += pos + 1;
+                if data[offset - 1] == b'\r' && data.get(offset) == Some(&b'\n') {
+                    offset += 1;
+                }
+            }
+            None => return &data[data.len()..],
+        }
+    }
+    &data[offset..]
+}
+
+/// Byte length of the first `records` records of `data` parsed with
+/// `dialect`, including their line terminators and any blank or comment
+/// lines between them.
+fn records_len(data: &[u8], dialect: &PotentialDialect, records: usize) -> usize {
+    if records == 0 {
+        return 0;
+    }
+    // The delimiter does not affect where records end
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .delimiter(dialect.delimiter)
+        .comment(dialect.comment)
+        .escape(dialect.escape)
+        .double_quote(dialect.escape.is_none())
+        .quoting(dialect.quote != Quote::None)
+        .quote(match dialect.quote {
+            Quote::Some(q) => q,
+            Quote::None => b'"',
+        })
+        .from_reader(data);
+    let mut record = csv::ByteRecord::new();
+    for _ in 0..records {
+        if !reader.read_byte_record(&mut record).unwrap_or(false) {
+            return data.len();
+        }
+    }
+    let mut len = (reader.position().byte() as usize).min(data.len());
+    // A CRLF record ends at the CR
+    if len > 0 && data[len - 1] == b'\r' && data.get(len) == Some(&b'\n') {
+        len += 1;
+    }
+    len
+}
+
+/// Number of lines in `data`, counting CRLF once and a final line without a
+/// terminator.
+fn count_lines(data: &[u8]) -> usize {
+    let terminators = data
+        .iter()
+        .enumerate()
+        .filter(|&(i, &b)| b == b'\n' || (b == b'\r' && data.get(i + 1) != Some(&b'\n')))
+        .count();
+    terminators + usize::from(data.last().is_some_and(|&b| b != b'\n' && b != b'\r'))
+}
+
 /// Detect structural preamble rows using field count consistency analysis.
 ///
 /// Identifies rows at the start that don't match the predominant field count
@@ -528,9 +1294,84 @@ fn detect_structural_preamble(table: &crate::tum::table::Table) -> usize {
     0
 }
 
+/// Labels of summary rows appended by spreadsheets and BI tools.
+const FOOTER_KEYWORDS: &[&str] = &[
+    "total",
+    "totals",
+    "subtotal",
+    "grand total",
+    "sum",
+    "average",
+    "avg",
+    "mean",
+];
+
+/// Detect trailing footer rows (summary, trailer and blank padding rows).
+///
+/// Mirrors `detect_structural_preamble` from the end of the table. Working
+/// backwards, a row is part of the footer if it is entirely empty, if its
+/// first non-empty cell is a summary label such as "Total" and its other cells
+/// are empty or numeric (the aggregates), or if it does not match the modal
+/// field count and has at most one non-empty cell (trailer lines such as
+/// "Generated by ..." or "Row count: 42").
+/// At least two rows after the structural preamble are always kept as data.
+///
+/// Only meaningful when the table extends to the end of the input.
+fn detect_footer(table: &crate::tum::table::Table, structural_preamble: usize) -> usize {
+    let n = table.rows.len();
+    if n < structural_preamble + 3 {
+        return 0;
+    }
+
+    let modal_count = table.modal_field_count();
+    let max_footer = n - structural_preamble - 2;
+
+    table
+        .rows
+        .iter()
+        .zip(&table.field_counts)
+        .rev()
+        .take(max_footer)
+        .take_while(|(row, field_count)| is_footer_row(row, **field_count, modal_count))
+        .count()
+}
+
+fn is_footer_row(row: &[String], field_count: usize, modal_count: usize) -> bool {
+    let non_empty: Vec<&str> = row
+        .iter()
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect();
+    let Some((first, rest)) = non_empty.split_first() else {
+        return true;
+    };
+
+    if is_footer_label(first)
+        && rest
+            .iter()
+            .all(|cell| crate::tum::type_detection::detect_cell_type(cell).is_numeric())
+    {
+        return true;
+    }
+
+    field_count != modal_count && rest.is_empty()
+}
+
+/// Whether `cell` is a summary keyword, optionally followed by numbers and
+/// punctuation only ("Total", "Total:", "Grand total 2024").
+fn is_footer_label(cell: &str) -> bool {
+    let lower = cell.to_lowercase();
+    FOOTER_KEYWORDS.iter().any(|keyword| {
+        lower
+            .strip_prefix(keyword)
+            .is_some_and(|rest| rest.chars().all(|c| !c.is_alphabetic() && c != '_'))
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::stream::MAX_RECORDS_BYTES;
 
     #[test]
     fn test_sniffer_builder() {
@@ -602,6 +1443,80 @@ mod tests {
         assert_eq!(metadata.dialect.quote, Quote::Some(b'"'));
     }
 
+    #[test]
+    fn test_candidates_ranked_with_selected_first() {
+        let data = b"name;age;city\nAlice;30;NYC\nBob;25;LA\n";
+        let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+
+        let candidates = &metadata.candidates;
+        assert_eq!(
+            candidates.len(),
+            generate_dialects_with_terminator(LineTerminator::LF).len()
+        );
+        assert_eq!(candidates[0].delimiter, metadata.dialect.delimiter);
+        assert_eq!(candidates[0].quote, metadata.dialect.quote);
+        assert_eq!(candidates[0].num_fields, metadata.num_fields);
+        // Runners-up are ordered by descending gamma
+        assert!(candidates[1..].windows(2).all(|w| w[0].gamma >= w[1].gamma));
+    }
+
+    #[test]
+    fn test_forced_encoding() {
+        // ISO-8859-15 has the euro sign at 0xA4, where Windows-1252 has the currency sign
+        let data: &[u8] = b"item;price\nbread;2\xa4\nmilk;1\xa4\ncheese;7\xa4\n";
+        let metadata = Sniffer::new()
+            .encoding(encoding_rs::ISO_8859_15)
+            .sniff_bytes(data)
+            .unwrap();
+        assert_eq!(metadata.encoding.name(), "ISO-8859-15");
+        assert_eq!(metadata.dialect.delimiter, b';');
+
+        let err = Sniffer::new()
+            .encoding(encoding_rs::UTF_8)
+            .sniff_bytes(data)
+            .unwrap_err();
+        assert!(matches!(err, SnifferError::EncodingMismatch { .. }));
+    }
+
+    #[test]
+    fn test_explain() {
+        let data = b"a,b,c\n1,2,3\n4,5,6\n";
+        assert!(
+            Sniffer::new()
+                .sniff_bytes(data)
+                .unwrap()
+                .explanation
+                .is_none()
+        );
+
+        let metadata = Sniffer::new().explain(true).sniff_bytes(data).unwrap();
+        let explanation = metadata.explanation.unwrap();
+        assert_eq!(explanation.candidates.len(), metadata.candidates.len());
+
+        let selected = &explanation.candidates[0];
+        assert_eq!(selected.delimiter, b',');
+        assert!(selected.factors.iter().any(|f| f.name == "uniformity"));
+        // Three rows trigger the small-sample penalty
+        assert!(
+            selected
+                .factors
+                .iter()
+                .any(|f| f.name == "small_sample_penalty" && f.value == 0.90)
+        );
+
+        let semicolon = explanation
+            .candidates
+            .iter()
+            .find(|c| c.delimiter == b';')
+            .unwrap();
+        assert!(
+            semicolon
+                .factors
+                .iter()
+                .any(|f| f.name == "single_field_penalty")
+        );
+    }
+
     #[test]
     fn test_sniff_empty() {
         let data = b"";
@@ -615,19 +1530,19 @@ mod tests {
     fn test_skip_preamble() {
         // Test with comment lines
         let data = b"# This is a comment\n# Another comment\nname,age\nAlice,30\n";
-        let (preamble_rows, remaining) = skip_preamble(data);
+        let (preamble_rows, remaining) = skip_preamble(data, b"#");
         assert_eq!(preamble_rows, 2);
         assert_eq!(remaining, b"name,age\nAlice,30\n");
 
         // Test without comment lines
         let data = b"name,age\nAlice,30\n";
-        let (preamble_rows, remaining) = skip_preamble(data);
+        let (preamble_rows, remaining) = skip_preamble(data, b"#");
         assert_eq!(preamble_rows, 0);
         assert_eq!(remaining, b"name,age\nAlice,30\n");
 
         // Test with whitespace before #
         let data = b"  # Indented comment\nname,age\n";
-        let (preamble_rows, remaining) = skip_preamble(data);
+        let (preamble_rows, remaining) = skip_preamble(data, b"#");
         assert_eq!(preamble_rows, 1);
         assert_eq!(remaining, b"name,age\n");
     }
@@ -644,6 +1559,55 @@ mod tests {
         assert_eq!(metadata.num_fields, 3);
     }
 
+    #[test]
+    fn test_skip_preamble_other_marker() {
+        let data = b"% header note\n%% another\nname,age\n";
+        let (preamble_rows, remaining) = skip_preamble(data, b"%");
+        assert_eq!(preamble_rows, 2);
+        assert_eq!(remaining, b"name,age\n");
+    }
+
+    #[test]
+    fn test_scattered_comment_lines() {
+        let data =
+            b"% export v2\nid;name;score\n1;alpha;3.5\n% page 2\n2;beta;4.0\n3;gamma;2.5\n% end\n";
+        let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+
+        assert_eq!(metadata.dialect.delimiter, b';');
+        assert_eq!(metadata.dialect.comment, Some(b'%'));
+        assert_eq!(metadata.dialect.header.num_preamble_rows, 1);
+        assert!(!metadata.dialect.flexible);
+        assert_eq!(metadata.num_fields, 3);
+        assert_eq!(metadata.fields, vec!["id", "name", "score"]);
+    }
+
+    #[test]
+    fn test_other_marker_keeps_leading_hash_rows() {
+        // Detected `%` marker
+        let data = b"#1,Ann\n#2,Bo\n% x\n3,Cy\n% y\n4,Di\n% z\n5,Ed\n";
+        let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+        assert_eq!(metadata.dialect.comment, Some(b'%'));
+        assert_eq!(metadata.dialect.header.num_preamble_rows, 0);
+        assert!(metadata.preamble.is_empty());
+
+        // Forced `%` marker
+        let data = b"#1,Ann\n#2,Bo\n3,Cy\n4,Di\n5,Ed\n";
+        let mut sniffer = Sniffer::new();
+        sniffer.comment(b'%');
+        let metadata = sniffer.sniff_bytes(data).unwrap();
+        assert_eq!(metadata.dialect.header.num_preamble_rows, 0);
+        assert_eq!(metadata.dialect.delimiter, b',');
+    }
+
+    #[test]
+    fn test_indented_comment_preamble() {
+        let data = b"  # exported\n\t# v2\nname,age\nAlice,30\nBob,25\nCy,41\n";
+        let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+        assert_eq!(metadata.dialect.comment, Some(b'#'));
+        assert_eq!(metadata.dialect.header.num_preamble_rows, 2);
+        assert_eq!(metadata.fields, vec!["name", "age"]);
+    }
+
     #[test]
     fn test_comment_preamble_propagated() {
         let data = b"# Comment 1\n# Comment 2\nname,age\nAlice,30\nBob,25\n";
@@ -718,6 +1682,423 @@ mod tests {
         assert_eq!(detect_structural_preamble(&table), 0);
     }
 
+    #[test]
+    fn test_detect_footer_function() {
+        use crate::tum::table::Table;
+
+        let rows: Vec<Vec<String>> = [
+            &["id", "amount"][..],
+            &["1", "10"],
+            &["2", "20"],
+            &["3", "30"],
+            &["Total", "60"],
+            &["", ""],
+            &["Generated by ReportServer"],
+        ]
+        .iter()
+        .map(|r| r.iter().map(|s| (*s).to_string()).collect())
+        .collect();
+        let mut table = Table::new();
+        table.field_counts = rows.iter().map(Vec::len).collect();
+        table.rows = rows;
+        table.update_modal_field_count();
+        assert_eq!(detect_footer(&table, 0), 3);
+
+        // A data row that merely contains a keyword later in the row is kept
+        table.rows.truncate(4);
+        table.field_counts.truncate(4);
+        table.rows.push(vec!["4".to_string(), "total".to_string()]);
+        table.field_counts.push(2);
+        assert_eq!(detect_footer(&table, 0), 0);
+
+        // The label may carry numbers and punctuation, but not other words,
+        // and the rest of the row must be aggregates
+        for (label, value, footer) in [
+            ("Grand total 2024:", "60", 1),
+            ("Sum Kim", "60", 0),
+            ("Sum", "Rome", 0),
+        ] {
+            table.rows[4] = vec![label.to_string(), value.to_string()];
+            assert_eq!(detect_footer(&table, 0), footer, "{label}");
+        }
+
+        // At least two data rows are always kept
+        assert_eq!(detect_footer(&table, 3), 0);
+    }
+
+    #[test]
+    fn test_name_starting_with_keyword_is_data() {
+        let data = b"Ann Lee,Rome\nBo Chan,Paris\nCy Diaz,Lyon\nDi Fox,Oslo\nSum Kim,Rome\n";
+        let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+        assert_eq!(metadata.dialect.header.num_footer_rows, 0);
+        assert!(!metadata.dialect.header.has_header_row);
+    }
+
+    #[test]
+    fn test_footer_excluded_from_types() {
+        let data = b"id,amount\n1,10\n2,20\n3,30\nTotal,60\n,\n";
+        let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+        assert_eq!(metadata.dialect.header.num_footer_rows, 2);
+        assert_eq!(metadata.types, vec![Type::Unsigned, Type::Unsigned]);
+    }
+
+    #[test]
+    fn test_footer_requires_eof() {
+        let data = b"id,amount\n1,10\n2,20\n3,30\nTotal,60\n5,50\n6,60\n";
+
+        // The sample stops on the "Total" row, which may not be the end of the file
+        let mut sniffer = Sniffer::new();
+        sniffer.sample_size(SampleSize::Bytes(34));
+        let metadata = sniffer
+            .sniff_reader(std::io::Cursor::new(data.to_vec()))
+            .unwrap();
+        assert_eq!(metadata.dialect.header.num_footer_rows, 0);
+
+        sniffer.sample_size(SampleSize::Records(4));
+        let metadata = sniffer.sniff_bytes(&data[..34]).unwrap();
+        assert_eq!(metadata.dialect.header.num_footer_rows, 0);
+    }
+
+    fn rows(data: &[&[&str]]) -> Vec<Vec<String>> {
+        data.iter()
+            .map(|r| r.iter().map(|s| (*s).to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_header_rows_grouping() {
+        let table = rows(&[
+            &["", "Revenue", "", "Cost", ""],
+            &["Region", "2023", "2024", "2023", "2024"],
+            &["North", "1500.50", "1620.00", "900.10", "950.00"],
+            &["South", "980.25", "1010.75", "610.00", "640.50"],
+            &["East", "2100.00", "2250.25", "1300.00", "1350.75"],
+        ]);
+        let header_rows = detect_header_rows(&table, true);
+        assert_eq!(
+            header_rows,
+            HeaderRows {
+                count: 2,
+                grouping: 1
+            }
+        );
+        assert_eq!(
+            combine_header_names(&table[..2], header_rows.grouping),
+            vec![
+                "Region",
+                "Revenue_2023",
+                "Revenue_2024",
+                "Cost_2023",
+                "Cost_2024"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_detect_header_rows_single() {
+        // A blank column name does not make the header a grouping row
+        let table = rows(&[
+            &["id", "", "name"],
+            &["1", "x", "alpha"],
+            &["2", "y", "beta"],
+            &["3", "z", "gamma"],
+        ]);
+        assert_eq!(detect_header_rows(&table, true).count, 1);
+
+        // Text data in a mostly numeric column is not a units row
+        let table = rows(&[
+            &["name", "score", "rank"],
+            &["Alice", "90", "1"],
+            &["Bob", "85", "2"],
+            &["Carol", "80", "3"],
+            &["Dan", "75", "4"],
+        ]);
+        assert_eq!(detect_header_rows(&table, true).count, 1);
+        assert_eq!(detect_header_rows(&table, false).count, 0);
+    }
+
+    #[test]
+    fn test_column_header_votes() {
+        let mut table = Table::new();
+        table.rows = rows(&[
+            &["2019", "sku", "name", ""],
+            &["1.5", "AB12", "Alice", "x"],
+            &["2.75", "CD34", "Bob", "y"],
+            &["12.25", "EF56", "Carol", "z"],
+        ]);
+        table.field_counts = vec![4; 4];
+        table.update_modal_field_count();
+        assert_eq!(
+            column_header_votes(&table, &body_column_types(&table)),
+            vec![
+                HeaderVote::Header,
+                HeaderVote::Header,
+                HeaderVote::Abstain,
+                HeaderVote::Abstain
+            ]
+        );
+
+        // An integer is data once the Float column also holds integers
+        table.rows[0] = vec!["3".into(), "GH78".into(), "Dan".into(), "w".into()];
+        table.rows[2][0] = "7".into();
+        assert_eq!(
+            column_header_votes(&table, &body_column_types(&table)),
+            vec![
+                HeaderVote::Data,
+                HeaderVote::Data,
+                HeaderVote::Abstain,
+                HeaderVote::Data
+            ]
+        );
+    }
+
+    #[test]
+    fn test_numeric_named_header() {
+        let data = b"2019,2020,2021\n1.5,2.25,3.75\n4.5,5.25,6.75\n7.5,8.25,9.75\n";
+        let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+        assert!(metadata.dialect.header.has_header_row);
+        assert_eq!(metadata.fields, vec!["2019", "2020", "2021"]);
+        assert_eq!(
+            metadata.dialect.header.column_votes,
+            vec![HeaderVote::Header; 3]
+        );
+    }
+
+    #[test]
+    fn test_candidate_delimiters() {
+        let data = b"id\x1fname\x1fcity\n1\x1fAnn|Lee\x1fOslo\n2\x1fBo\x1fRome\n3\x1fCy\x1fLima\n";
+        let mut sniffer = Sniffer::new();
+        sniffer.candidate_delimiters(b"|\x1f|");
+        let metadata = sniffer.sniff_bytes(data).unwrap();
+        assert_eq!(metadata.dialect.delimiter, 0x1f);
+        assert!(
+            metadata
+                .candidates
+                .iter()
+                .all(|c| [b'|', 0x1f].contains(&c.delimiter))
+        );
+
+        // A custom candidate has no delimiter penalty
+        sniffer.explain(true);
+        let metadata = sniffer.sniff_bytes(data).unwrap();
+        let explanation = metadata.explanation.unwrap();
+        assert!(
+            explanation.candidates[0]
+                .factors
+                .iter()
+                .all(|f| f.name != "delimiter_penalty")
+        );
+
+        sniffer.candidate_delimiters(&[]);
+        assert!(matches!(
+            sniffer.sniff_bytes(data),
+            Err(SnifferError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_unicode_separator() {
+        let data = "id§name§city\n1§\"Ann § Lee\"§Oslo\n2§Bo§Rome\n3§Cy§Lima\n4§Di§Kyiv\n";
+        let metadata = Sniffer::new().sniff_bytes(data.as_bytes()).unwrap();
+        assert_eq!(metadata.dialect.separator, Separator::Char('§'));
+        assert_eq!(metadata.dialect.delimiter, 0x1f);
+        assert_eq!(metadata.num_fields, 3);
+        assert_eq!(metadata.fields, vec!["id", "name", "city"]);
+        assert!(!metadata.dialect.flexible);
+
+        // The byte 0xA7 inside other characters is never a delimiter
+        let data = "a,b\nç,1\nç,2\nç,3\n";
+        let metadata = Sniffer::new().sniff_bytes(data.as_bytes()).unwrap();
+        assert_eq!(metadata.dialect.separator, Separator::Byte(b','));
+    }
+
+    #[test]
+    fn test_multi_char_separator() {
+        for separator in ["~|~", "::", "::~"] {
+            let data = [
+                "id", "name", "amount", "1", "Ann", "10.5", "2", "Bo", "7.25", "3", "Cy", "3.0",
+            ]
+            .chunks(3)
+            .map(|row| row.join(separator) + "\n")
+            .collect::<String>();
+            let metadata = Sniffer::new().sniff_bytes(data.as_bytes()).unwrap();
+            assert_eq!(
+                metadata.dialect.separator,
+                Separator::Sequence(separator.as_bytes().to_vec()),
+                "{separator:?}"
+            );
+            assert_eq!(metadata.fields, vec!["id", "name", "amount"]);
+            assert_eq!(metadata.types[2], Type::Float);
+        }
+    }
+
+    #[test]
+    fn test_repeated_delimiter_is_empty_columns() {
+        for delimiter in [b',', b'|', b'\t'] {
+            let data = "a,,b,,c\n1,,2,,3\n4,,5,,6\n7,,8,,9\n"
+                .replace(',', &char::from(delimiter).to_string());
+            let metadata = Sniffer::new().sniff_bytes(data.as_bytes()).unwrap();
+            assert_eq!(metadata.dialect.separator, Separator::Byte(delimiter));
+            assert_eq!(metadata.num_fields, 5);
+        }
+    }
+
+    #[test]
+    fn test_scoring_config() {
+        let data = b"name\nAnn\nBo\nCy\nDi\n";
+        assert_eq!(
+            Sniffer::new().sniff_bytes(data).unwrap().dialect.delimiter,
+            b','
+        );
+
+        // Semicolon wins the tie once it has the highest priority
+        let mut scoring = ScoringConfig::default();
+        scoring.delimiter_priorities.insert(b';', 11);
+        let mut sniffer = Sniffer::new();
+        sniffer.scoring(scoring);
+        assert_eq!(sniffer.sniff_bytes(data).unwrap().dialect.delimiter, b';');
+
+        // Zero bonus thresholds do not divide by zero
+        let mut sniffer = Sniffer::new();
+        sniffer.scoring(ScoringConfig {
+            row_bonus_rows: 0,
+            field_bonus_fields: 0,
+            ..ScoringConfig::default()
+        });
+        let metadata = sniffer.sniff_bytes(b"a,b\n1,2\n3,4\n5,6\n").unwrap();
+        assert_eq!(metadata.dialect.delimiter, b',');
+        assert!(metadata.candidates.iter().all(|c| !c.gamma.is_nan()));
+    }
+
+    #[test]
+    fn test_default_scoring_golden_gammas() {
+        // Gammas from the scoring constants before they moved into ScoringConfig
+        fn gamma(sniffer: &Sniffer, data: &[u8], delimiter: u8) -> f64 {
+            let metadata = sniffer.sniff_bytes(data).unwrap();
+            metadata
+                .candidates
+                .iter()
+                .find(|c| c.delimiter == delimiter && c.quote == Quote::Some(b'"'))
+                .unwrap()
+                .gamma
+        }
+
+        let data = b"id;name;amount\n1;Ann;10,5\n2;Bo;7,25\n3;Cy;3,0\n";
+        let sniffer = Sniffer::new();
+        assert_eq!(gamma(&sniffer, data, b';'), 0.82575);
+        assert_eq!(gamma(&sniffer, data, b','), 0.6333034890104534);
+        assert_eq!(gamma(&sniffer, data, b'\t'), 0.37350000000000005);
+
+        // Relaxed hash penalty for a large multi-field table
+        let mut data = String::from("a#b#c\n");
+        for i in 0..60 {
+            data.push_str(&format!("{i}#x{i}#{i}.5\n"));
+        }
+        assert_eq!(gamma(&sniffer, data.as_bytes(), b'#'), 0.8777295081967212);
+        assert_eq!(gamma(&sniffer, data.as_bytes(), b','), 0.455);
+
+        // Neutral prior for a user-supplied candidate
+        let mut sniffer = Sniffer::new();
+        sniffer.candidate_delimiters(b"!");
+        let data = b"a!b!c\n1!2!3\n4!5!6\n7!8!9\n10!11!12\n";
+        assert_eq!(gamma(&sniffer, data, b'!'), 0.911);
+    }
+
+    #[test]
+    fn test_whitespace_run_separator() {
+        let data = b"Filesystem     Size  Used Avail Use%\n/dev/sda1       50G   20G   30G  40%\ntmpfs          1.0G     0  1.0G   0%\n/dev/sdb1      200G  150G   50G  75%\nudev           7.8G     0  7.8G   0%\n";
+        let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+        assert_eq!(metadata.dialect.separator, Separator::Whitespace);
+        assert!(!metadata.dialect.flexible);
+        assert_eq!(metadata.num_fields, 5);
+
+        // Single spaces stay a plain space delimiter
+        let data = b"a b c\n1 2 3\n4 5 6\n7 8 9\n";
+        let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+        assert_eq!(metadata.dialect.separator, Separator::Byte(b' '));
+    }
+
+    #[test]
+    fn test_fixed_width_layout() {
+        let data = b"NAME        CITY        AMOUNT\nJohn Smith  New York     12.50\nAnn Lee     Paris         3.00\nBo          Oslo        100.25\nCy Young    Rio de Jan    7.75\n";
+        let Layout::FixedWidth(layout) = Sniffer::new().sniff_layout_bytes(data).unwrap() else {
+            panic!("expected a fixed-width layout");
+        };
+        assert!(layout.has_header_row);
+        assert!(layout.gamma > layout.delimited_gamma);
+        let columns: Vec<_> = layout
+            .columns
+            .iter()
+            .map(|c| (c.name.as_str(), c.start, c.end, c.field_type))
+            .collect();
+        assert_eq!(
+            columns,
+            vec![
+                ("NAME", 0, 10, Type::Text),
+                ("CITY", 12, 22, Type::Text),
+                ("AMOUNT", 24, 30, Type::Float),
+            ]
+        );
+
+        // Delimited data keeps its dialect
+        let data =
+            b"name,city,amount\nJohn Smith,New York,12.50\nAnn Lee,Paris,3.00\nBo,Oslo,100.25\n";
+        let Layout::Delimited(metadata) = Sniffer::new().sniff_layout_bytes(data).unwrap() else {
+            panic!("expected a delimited layout");
+        };
+        assert_eq!(metadata.dialect.delimiter, b',');
+    }
+
+    #[test]
+    fn test_skip_lines() {
+        assert_eq!(skip_lines(b"a\r\nb\rc\nd", 3), b"d");
+        assert_eq!(skip_lines(b"a\nb", 0), b"a\nb");
+        assert_eq!(skip_lines(b"a\nb", 5), b"");
+    }
+
+    #[test]
+    fn test_records_len() {
+        let dialect = PotentialDialect::new(b',', Quote::Some(b'"'), LineTerminator::CRLF);
+        let data = b"title\r\n\r\n\"a\r\nb\",c\r\nx,y\r\n";
+        assert_eq!(records_len(data, &dialect, 0), 0);
+        assert_eq!(records_len(data, &dialect, 1), 7);
+        assert_eq!(records_len(data, &dialect, 2), 19);
+        assert_eq!(records_len(data, &dialect, 9), data.len());
+        assert_eq!(count_lines(&data[..19]), 4);
+        assert_eq!(count_lines(b"a\rb"), 2);
+    }
+
+    #[test]
+    fn test_forced_preamble_rows() {
+        let data = b"Quarterly report\nRegion: all, Currency: EUR\n\nname,amount\nx,1\ny,2\nz,3\n";
+        let mut sniffer = Sniffer::new();
+        sniffer.preamble_rows(3);
+        let metadata = sniffer.sniff_bytes(data).unwrap();
+        assert_eq!(metadata.dialect.header.num_preamble_rows, 3);
+        assert_eq!(metadata.dialect.delimiter, b',');
+        assert_eq!(metadata.fields, vec!["name", "amount"]);
+    }
+
+    #[test]
+    fn test_forced_header_and_comment() {
+        let data = b"1,2,3\n4,5,6\n@ note\n7,8,9\n";
+        let mut sniffer = Sniffer::new();
+        sniffer.header(true).comment(b'@');
+        let metadata = sniffer.sniff_bytes(data).unwrap();
+        assert!(metadata.dialect.header.has_header_row);
+        assert_eq!(metadata.dialect.header.num_header_rows, 1);
+        assert_eq!(metadata.dialect.comment, Some(b'@'));
+        assert_eq!(metadata.fields, vec!["1", "2", "3"]);
+
+        let mut sniffer = Sniffer::new();
+        sniffer.header(false);
+        let metadata = sniffer
+            .sniff_bytes(b"name,age\nAlice,30\nBob,25\n")
+            .unwrap();
+        assert!(!metadata.dialect.header.has_header_row);
+        assert_eq!(metadata.types, vec![Type::Text, Type::Text]);
+    }
+
     #[test]
     fn test_avg_record_len_calculated_from_data() {
         // Test that avg_record_len uses raw bytes, not parsed content
@@ -0,0 +1,262 @@
+//! Incremental, push-based sniffing for inputs that cannot be read in one go.
+//!
+//! `SniffState` buffers chunks until the configured `SampleSize` is satisfied,
+//! so sockets, pipes and decompression streams can be sniffed without first
+//! spooling them to a temporary file.
+
+use crate::error::{Result, SnifferError};
+use crate::metadata::{Layout, Metadata};
+use crate::sample::SampleSize;
+use crate::sniffer::Sniffer;
+
+/// Maximum buffer size for `SampleSize::Records` mode (100 MB).
+pub(crate) const MAX_RECORDS_BYTES: usize = 100 * 1024 * 1024;
+
+/// Maximum buffer size for `SampleSize::All` mode (1 GB).
+const MAX_ALL_BYTES: usize = 1024 * 1024 * 1024;
+
+/// Incremental sniffing state fed with chunks of input.
+///
+/// Created by [`Sniffer::sniff_state`]. Chunks passed to [`feed`](Self::feed)
+/// are buffered until the sample size is satisfied; bytes beyond the sample
+/// are discarded.
+///
+/// # Example
+///
+/// ```
+/// use csv_nose::Sniffer;
+///
+/// let sniffer = Sniffer::new();
+/// let mut state = sniffer.sniff_state();
+/// for chunk in [&b"name,age\nAlice,"[..], &b"30\nBob,25\n"[..]] {
+///     if !state.feed(chunk) {
+///         break;
+///     }
+/// }
+/// let metadata = state.finish().unwrap();
+/// assert_eq!(metadata.dialect.delimiter, b',');
+/// ```
+#[derive(Debug, Clone)]
+pub struct SniffState {
+    /// Sniffer configuration used by `finish`.
+    sniffer: Sniffer,
+    /// Buffered sample bytes.
+    buffer: Vec<u8>,
+    /// Sample limit for `SampleSize::Records`, fixed once the initial estimate is filled.
+    records_limit: Option<usize>,
+    /// Whether bytes beyond the sample limit were fed.
+    truncated: bool,
+}
+
+impl SniffState {
+    /// Create a new state for the given sniffer configuration.
+    pub(crate) fn new(sniffer: Sniffer) -> Self {
+        Self {
+            sniffer,
+            buffer: Vec::new(),
+            records_limit: None,
+            truncated: false,
+        }
+    }
+
+    /// Feed the next chunk of input.
+    ///
+    /// Returns `true` while more data is wanted, and `false` once the sample
+    /// size is satisfied (further chunks are discarded).
+    pub fn feed(&mut self, mut chunk: &[u8]) -> bool {
+        while !chunk.is_empty() {
+            let limit = self.limit();
+            if self.buffer.len() >= limit {
+                self.truncated = true;
+                break;
+            }
+            let take = (limit - self.buffer.len()).min(chunk.len());
+            self.buffer.extend_from_slice(&chunk[..take]);
+            chunk = &chunk[take..];
+            if self.records_limit.is_none()
+                && let SampleSize::Records(n) = self.sniffer.get_sample_size()
+                && self.buffer.len() >= records_estimate(n)
+            {
+                self.records_limit = Some(self.limit());
+            }
+        }
+        !self.is_complete()
+    }
+
+    /// Returns true once the sample size is satisfied.
+    pub fn is_complete(&self) -> bool {
+        self.buffer.len() >= self.limit()
+    }
+
+    /// Number of bytes still wanted before the sample size is satisfied.
+    pub fn remaining(&self) -> usize {
+        self.limit().saturating_sub(self.buffer.len())
+    }
+
+    /// Size of the next read from a pull-based source: up to `max` bytes
+    /// while the sample is incomplete, then a single byte to probe whether the
+    /// input continues past the sample.
+    pub(crate) fn next_read_len(&self, max: usize) -> usize {
+        if self.is_complete() {
+            1
+        } else {
+            self.remaining().min(max)
+        }
+    }
+
+    /// Feed the result of a read sized by `next_read_len`.
+    ///
+    /// Returns `Some(reached_eof)` once reading should stop: `true` when the
+    /// read was empty (end of input), `false` when the probe found more data.
+    pub(crate) fn feed_read(&mut self, chunk: &[u8]) -> Option<bool> {
+        if chunk.is_empty() {
+            return Some(true);
+        }
+        let probing = self.is_complete();
+        self.feed(chunk);
+        probing.then_some(false)
+    }
+
+    /// The bytes buffered so far.
+    pub fn sample(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Sniff the buffered sample.
+    ///
+    /// Call this at the end of the input or once [`feed`](Self::feed) returns
+    /// `false`. When the sample size was reached, the input is assumed to
+    /// continue past the sample, so trailing rows are not reported as a footer.
+    pub fn finish(self) -> Result<Metadata> {
+        let reached_eof = !self.is_complete();
+        self.finish_with_eof(reached_eof)
+    }
+
+    /// Sniff the buffered sample for a delimited or fixed-width layout.
+    ///
+    /// See [`finish`](Self::finish) and [`Sniffer::sniff_layout_bytes`].
+    pub fn finish_layout(self) -> Result<Layout> {
+        let reached_eof = !self.is_complete();
+        self.finish_layout_with_eof(reached_eof)
+    }
+
+    /// Sniff the buffered sample, with the caller knowing whether the input
+    /// ended within the sample.
+    pub(crate) fn finish_with_eof(self, reached_eof: bool) -> Result<Metadata> {
+        let reached_eof = self.check_sample(reached_eof)?;
+        self.sniffer.sniff_sample(&self.buffer, reached_eof)
+    }
+
+    /// Like `finish_with_eof`, also considering fixed-width layouts.
+    pub(crate) fn finish_layout_with_eof(self, reached_eof: bool) -> Result<Layout> {
+        let reached_eof = self.check_sample(reached_eof)?;
+        self.sniffer.sniff_layout_sample(&self.buffer, reached_eof)
+    }
+
+    /// Fail on an empty sample and warn when it was truncated, returning
+    /// whether the sample reaches the end of the input.
+    fn check_sample(&self, reached_eof: bool) -> Result<bool> {
+        if self.buffer.is_empty() {
+            return Err(SnifferError::EmptyData);
+        }
+
+        let reached_eof = reached_eof && !self.truncated;
+        match self.sniffer.get_sample_size() {
+            SampleSize::All if !reached_eof => eprintln!(
+                "warning: input exceeds 1 GB; sniffing on truncated sample — results may be inaccurate"
+            ),
+            SampleSize::Records(_) if !reached_eof && self.buffer.len() >= MAX_RECORDS_BYTES => {
+                eprintln!(
+                    "warning: Records sample capped at 100 MB; \
+                     sniff result may be approximate for very large inputs"
+                );
+            }
+            _ => {}
+        }
+        Ok(reached_eof)
+    }
+
+    /// Current sample limit in bytes.
+    ///
+    /// For `SampleSize::Records` the limit depends on the first
+    /// `records_estimate` bytes; `feed` caches it once they are buffered.
+    fn limit(&self) -> usize {
+        match self.sniffer.get_sample_size() {
+            SampleSize::Bytes(n) => n,
+            SampleSize::All => MAX_ALL_BYTES,
+            SampleSize::Records(n) => {
+                if let Some(limit) = self.records_limit {
+                    return limit;
+                }
+                let estimated_size = records_estimate(n);
+                if self.buffer.len() < estimated_size {
+                    return estimated_size;
+                }
+                // Count newlines to see if we have enough records, and read more if not
+                let newlines = bytecount::count(&self.buffer[..estimated_size], b'\n');
+                if newlines < n {
+                    let additional = (n - newlines).saturating_mul(2048).min(MAX_RECORDS_BYTES);
+                    estimated_size + additional
+                } else {
+                    estimated_size
+                }
+            }
+        }
+    }
+}
+
+/// Initial sample size for `n` records: ~1KB per record, with a minimum.
+fn records_estimate(n: usize) -> usize {
+    n.saturating_mul(1024).clamp(8192, MAX_RECORDS_BYTES)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_feed_stops_at_byte_limit() {
+        let mut sniffer = Sniffer::new();
+        sniffer.sample_size(SampleSize::Bytes(10));
+        let mut state = sniffer.sniff_state();
+
+        assert!(state.feed(b"a,b\n1,"));
+        assert_eq!(state.remaining(), 4);
+        assert!(!state.feed(b"2\n3,4\n5,6\n"));
+        assert_eq!(state.sample(), b"a,b\n1,2\n3,");
+        assert!(state.truncated);
+    }
+
+    #[test]
+    fn test_records_limit_extends_when_short() {
+        let mut sniffer = Sniffer::new();
+        sniffer.sample_size(SampleSize::Records(10));
+        let mut state = sniffer.sniff_state();
+
+        // 10 records estimate 10 KB; a newline-free first 10 KB asks for 10 * 2 KB more
+        assert!(state.feed(&vec![b'x'; 10 * 1024]));
+        assert_eq!(state.remaining(), 10 * 2048);
+    }
+
+    #[test]
+    fn test_finish_in_chunks_matches_bytes() {
+        let data = b"id,amount\n1,10\n2,20\n3,30\nTotal,60\n";
+        let sniffer = Sniffer::new();
+        let mut state = sniffer.sniff_state();
+        for chunk in data.chunks(3) {
+            state.feed(chunk);
+        }
+        let streamed = state.finish().unwrap();
+        let direct = sniffer.sniff_bytes(data).unwrap();
+
+        assert_eq!(streamed.dialect, direct.dialect);
+        assert_eq!(streamed.dialect.header.num_footer_rows, 1);
+        assert_eq!(streamed.types, direct.types);
+    }
+
+    #[test]
+    fn test_finish_empty() {
+        let state = Sniffer::new().sniff_state();
+        assert!(matches!(state.finish(), Err(SnifferError::EmptyData)));
+    }
+}
@@ -0,0 +1,173 @@
+//! Detection of the comment marker used by a file.
+//!
+//! A comment line starts with the marker after optional spaces and tabs, the
+//! same rule used when the leading comment lines are skipped. Lines may
+//! appear anywhere in the sample, not only in a leading block.
+
+use super::potential_dialects::DELIMITERS;
+
+/// Candidate comment markers, in priority order for ties.
+pub const COMMENT_MARKERS: &[&[u8]] = &[b"#", b"//", b"%", b";", b"!"];
+
+/// A marker is rejected when it occurs in at least this fraction of the
+/// non-comment lines, since it is then more likely a delimiter or data.
+const MAX_BODY_OCCURRENCE: f64 = 0.5;
+
+/// Detect the comment marker used by `data`, if any.
+///
+/// Returns the marker bytes from `COMMENT_MARKERS` with the most comment lines.
+/// A marker only qualifies if at least one line starts with it and it appears
+/// in fewer than half of the remaining lines.
+pub fn detect_comment_marker(data: &[u8]) -> Option<&'static [u8]> {
+    let lines = non_empty_lines(data);
+    if lines.is_empty() {
+        return None;
+    }
+
+    let mut best: Option<(&'static [u8], usize)> = None;
+    for &marker in COMMENT_MARKERS {
+        let comment_lines = lines
+            .iter()
+            .filter(|line| is_comment_line(line, marker))
+            .count();
+        if comment_lines == 0 || comment_lines == lines.len() {
+            continue;
+        }
+
+        let body_lines = lines.len() - comment_lines;
+        let body_hits = lines
+            .iter()
+            .filter(|line| !is_comment_line(line, marker) && contains(line, marker))
+            .count();
+        if body_hits as f64 >= body_lines as f64 * MAX_BODY_OCCURRENCE {
+            continue;
+        }
+
+        if best.is_none_or(|(_, count)| comment_lines > count) {
+            best = Some((marker, comment_lines));
+        }
+    }
+
+    best.map(|(marker, _)| marker)
+}
+
+/// The single byte passed to `csv::ReaderBuilder::comment` for a marker, or
+/// `None` when skipping marker lines as comments could drop data rows.
+///
+/// Multi-byte markers such as `//` have no single-byte equivalent (`/` would
+/// also match rows such as `/usr/bin,10`), so they are only skipped as a
+/// leading preamble. A single-byte marker is used when all of its lines form
+/// a leading block, or when none of them has the modal delimiter count of the
+/// other lines for any candidate delimiter (so they do not parse as data).
+pub fn comment_byte(data: &[u8], marker: &[u8]) -> Option<u8> {
+    let &[byte] = marker else {
+        return None;
+    };
+
+    let lines = non_empty_lines(data);
+    let leading = lines
+        .iter()
+        .take_while(|line| is_comment_line(line, marker))
+        .count();
+    let (marker_lines, body_lines): (Vec<&[u8]>, Vec<&[u8]>) = lines[leading..]
+        .iter()
+        .partition(|line| is_comment_line(line, marker));
+    if marker_lines.is_empty() {
+        return Some(byte);
+    }
+
+    let looks_like_data = DELIMITERS.iter().any(|&delimiter| {
+        modal_count(&body_lines, delimiter).is_some_and(|modal| {
+            marker_lines
+                .iter()
+                .any(|line| count(line, delimiter) == modal)
+        })
+    });
+    (!looks_like_data).then_some(byte)
+}
+
+/// Whether `line` starts with `marker` after optional spaces and tabs.
+pub fn is_comment_line(line: &[u8], marker: &[u8]) -> bool {
+    let start = line
+        .iter()
+        .position(|&b| b != b' ' && b != b'\t')
+        .unwrap_or(line.len());
+    line[start..].starts_with(marker)
+}
+
+/// The most common non-zero number of `delimiter` bytes per line.
+fn modal_count(lines: &[&[u8]], delimiter: u8) -> Option<usize> {
+    let mut counts = std::collections::HashMap::new();
+    for line in lines {
+        let n = count(line, delimiter);
+        if n > 0 {
+            *counts.entry(n).or_insert(0usize) += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .max_by_key(|&(n, freq)| (freq, n))
+        .map(|(n, _)| n)
+}
+
+fn count(line: &[u8], byte: u8) -> usize {
+    line.iter().filter(|&&b| b == byte).count()
+}
+
+fn non_empty_lines(data: &[u8]) -> Vec<&[u8]> {
+    data.split(|&b| b == b'\n' || b == b'\r')
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack.windows(needle.len()).any(|w| w == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_scattered_percent_comments() {
+        let data = b"% exported 2024\na,b,c\n1,2,3\n% checkpoint\n4,5,6\n";
+        assert_eq!(detect_comment_marker(data), Some(&b"%"[..]));
+    }
+
+    #[test]
+    fn test_detect_double_slash() {
+        let data = b"// generated\nx;y\n1;2\n// end\n";
+        let marker = detect_comment_marker(data).unwrap();
+        assert_eq!(marker, b"//");
+        assert_eq!(comment_byte(data, marker), None);
+    }
+
+    #[test]
+    fn test_comment_byte_only_for_non_data_lines() {
+        let data = b"% exported 2024\na,b,c\n1,2,3\n% checkpoint\n4,5,6\n";
+        assert_eq!(comment_byte(data, b"%"), Some(b'%'));
+
+        // Marker lines that split like the other rows are data
+        let data = b"id,name\n#1,Ann\n#2,Bo\n3,Cy\n4,Di\n5,Ed\n";
+        assert_eq!(comment_byte(data, b"#"), None);
+    }
+
+    #[test]
+    fn test_delimiter_is_not_a_comment() {
+        // Rows with an empty first field start with the delimiter
+        let data = b"a;b;c\n;2;3\n4;5;6\n;8;9\n";
+        assert_eq!(detect_comment_marker(data), None);
+    }
+
+    #[test]
+    fn test_detect_indented_marker() {
+        let data = b"  # exported\n\t# v2\na,b\n1,2\n";
+        assert_eq!(detect_comment_marker(data), Some(&b"#"[..]));
+        assert_eq!(comment_byte(data, b"#"), Some(b'#'));
+    }
+
+    #[test]
+    fn test_no_comments() {
+        assert_eq!(detect_comment_marker(b"a,b\n1,2\n"), None);
+    }
+}
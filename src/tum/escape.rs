@@ -0,0 +1,112 @@
+//! Detection of how embedded quotes are escaped inside quoted fields.
+//!
+//! RFC 4180 escapes a quote inside a quoted field by doubling it (`""`), while
+//! many database exports (notably MySQL) use a backslash (`\"`). Parsing one
+//! style with the other splits fields in the wrong places, so the escape style
+//! is detected per candidate dialect before the table is parsed.
+
+/// Counts of embedded-quote escapes observed inside quoted fields.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EscapeCounts {
+    /// Number of `\<quote>` sequences inside quoted fields.
+    pub backslash: usize,
+    /// Number of `<quote><quote>` sequences inside quoted fields.
+    pub doubled: usize,
+}
+
+impl EscapeCounts {
+    /// Scan `data` with a quote-aware state machine for the given delimiter
+    /// and quote character.
+    ///
+    /// A quoted field only starts when the quote character is the first byte of
+    /// a field, so apostrophes and stray quotes inside unquoted fields are
+    /// ignored. Inside a quoted field, a backslash consumes the following byte
+    /// and a doubled quote is read as an escaped quote.
+    pub fn new(data: &[u8], delimiter: u8, quote: u8) -> Self {
+        let mut counts = Self::default();
+        let mut in_quotes = false;
+        let mut field_start = true;
+        let mut i = 0;
+
+        while i < data.len() {
+            let byte = data[i];
+            if in_quotes {
+                if byte == b'\\' && i + 1 < data.len() {
+                    if data[i + 1] == quote {
+                        counts.backslash += 1;
+                    }
+                    i += 2;
+                    continue;
+                }
+                if byte == quote {
+                    if data.get(i + 1) == Some(&quote) {
+                        counts.doubled += 1;
+                        i += 2;
+                        continue;
+                    }
+                    in_quotes = false;
+                }
+            } else if byte == delimiter || byte == b'\n' || byte == b'\r' {
+                field_start = true;
+                i += 1;
+                continue;
+            } else if field_start && byte == quote {
+                in_quotes = true;
+            }
+            field_start = false;
+            i += 1;
+        }
+
+        counts
+    }
+
+    /// The escape character to configure, or `None` for RFC 4180 doubling.
+    pub const fn escape(&self) -> Option<u8> {
+        if self.backslash > self.doubled {
+            Some(b'\\')
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backslash_escapes() {
+        let data = b"id,quote\n1,\"She said \\\"hi\\\"\"\n2,\"a \\\"b\\\" c\"\n";
+        let counts = EscapeCounts::new(data, b',', b'"');
+        assert_eq!(counts.backslash, 4);
+        assert_eq!(counts.doubled, 0);
+        assert_eq!(counts.escape(), Some(b'\\'));
+    }
+
+    #[test]
+    fn test_doubled_quotes() {
+        let data = b"id,quote\n1,\"She said \"\"hi\"\"\"\n2,\"plain\"\n";
+        let counts = EscapeCounts::new(data, b',', b'"');
+        assert_eq!(counts.backslash, 0);
+        assert_eq!(counts.doubled, 2);
+        assert_eq!(counts.escape(), None);
+    }
+
+    #[test]
+    fn test_backslash_outside_quotes_ignored() {
+        // Windows paths in unquoted fields are not escapes
+        let data = b"path,size\nC:\\\"dir,10\nD:\\x,20\n";
+        let counts = EscapeCounts::new(data, b',', b'"');
+        assert_eq!(counts.backslash, 0);
+        assert_eq!(counts.escape(), None);
+    }
+
+    #[test]
+    fn test_escaped_backslash_before_closing_quote() {
+        // `\\` is an escaped backslash, so the following quote closes the field
+        let data = b"a,b\n\"dir\\\\\",x\n\"say \\\"y\\\"\",z\n";
+        let counts = EscapeCounts::new(data, b',', b'"');
+        assert_eq!(counts.backslash, 2);
+        assert_eq!(counts.escape(), Some(b'\\'));
+    }
+}
@@ -0,0 +1,117 @@
+//! Detection of fixed-width column layouts.
+//!
+//! Columns are separated by character positions that are blank on every line
+//! of the sample (a space, or past the end of a short line). Offsets are in
+//! characters, so multi-byte UTF-8 text stays aligned.
+
+use std::ops::Range;
+
+use super::table::Table;
+
+/// Minimum number of lines needed to trust aligned blank positions.
+const MIN_LINES: usize = 3;
+
+/// Split `data` into the lines used for layout detection: blank lines are
+/// skipped and at most `max_rows` lines are kept (0 = no limit).
+///
+/// Returns `None` if any line contains a tab, since tab stops make character
+/// positions ambiguous.
+pub fn layout_lines(data: &[u8], max_rows: usize) -> Option<Vec<Vec<char>>> {
+    let text = String::from_utf8_lossy(data);
+    let limit = if max_rows == 0 { usize::MAX } else { max_rows };
+    let lines: Vec<Vec<char>> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .take(limit)
+        .map(|line| line.chars().collect())
+        .collect();
+    if lines.iter().any(|line| line.contains(&'\t')) {
+        return None;
+    }
+    Some(lines)
+}
+
+/// Find the column ranges of a fixed-width layout: maximal runs of
+/// positions that are non-blank on at least one line.
+///
+/// Returns `None` with fewer than `MIN_LINES` lines or fewer than two columns.
+pub fn detect_columns(lines: &[Vec<char>]) -> Option<Vec<Range<usize>>> {
+    if lines.len() < MIN_LINES {
+        return None;
+    }
+
+    let width = lines.iter().map(Vec::len).max().unwrap_or(0);
+    let mut used = vec![false; width];
+    for line in lines {
+        for (i, &c) in line.iter().enumerate() {
+            if c != ' ' {
+                used[i] = true;
+            }
+        }
+    }
+
+    let mut columns = Vec::new();
+    let mut start = None;
+    for (i, &is_used) in used.iter().enumerate() {
+        match (start, is_used) {
+            (None, true) => start = Some(i),
+            (Some(s), false) => {
+                columns.push(s..i);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        columns.push(s..width);
+    }
+
+    (columns.len() >= 2).then_some(columns)
+}
+
+/// Slice every line into trimmed cells at the column ranges.
+pub fn slice_table(lines: &[Vec<char>], columns: &[Range<usize>]) -> Table {
+    let mut table = Table::new();
+    for line in lines {
+        let row: Vec<String> = columns
+            .iter()
+            .map(|range| {
+                let start = range.start.min(line.len());
+                let end = range.end.min(line.len());
+                line[start..end]
+                    .iter()
+                    .collect::<String>()
+                    .trim()
+                    .to_string()
+            })
+            .collect();
+        table.field_counts.push(row.len());
+        table.rows.push(row);
+    }
+    table.update_modal_field_count();
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_columns() {
+        let data = b"NAME        CITY        AMOUNT\nJohn Smith  New York     12.50\nAnn Lee     Paris         3.00\nBo          Oslo        100.25\n";
+        let lines = layout_lines(data, 0).unwrap();
+        let columns = detect_columns(&lines).unwrap();
+        assert_eq!(columns, vec![0..10, 12..20, 24..30]);
+
+        let table = slice_table(&lines, &columns);
+        assert_eq!(table.rows[1], vec!["John Smith", "New York", "12.50"]);
+        assert_eq!(table.rows[3], vec!["Bo", "Oslo", "100.25"]);
+    }
+
+    #[test]
+    fn test_detect_columns_rejects_unaligned() {
+        let lines = layout_lines(b"a b c\nab cd\nabc de\n", 0).unwrap();
+        assert_eq!(detect_columns(&lines), None);
+        assert_eq!(layout_lines(b"a\tb\n", 0), None);
+    }
+}
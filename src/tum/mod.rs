@@ -1,8 +1,12 @@
 //! Table Uniformity Method (TUM) for CSV dialect detection.
 
+pub mod comment;
+pub mod escape;
+pub mod fixed_width;
 pub mod potential_dialects;
 pub mod regexes;
 pub mod score;
+pub mod separator;
 pub mod table;
 pub mod type_detection;
 pub mod uniformity;
@@ -1,16 +1,26 @@
 //! Generation of potential CSV dialect combinations.
 
-use crate::metadata::Quote;
+pub use crate::metadata::LineTerminator;
+use crate::metadata::{Quote, Separator};
 
 /// A potential CSV dialect to test.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PotentialDialect {
-    /// Field delimiter character.
+    /// Field delimiter character (a stand-in byte for multi-byte separators).
     pub delimiter: u8,
+    /// The field separator as it appears in the data.
+    pub separator: Separator,
     /// Quote character configuration.
     pub quote: Quote,
     /// Line terminator sequence.
     pub line_terminator: LineTerminator,
+    /// Escape character for quotes inside quoted fields (`None` = doubled quotes).
+    pub escape: Option<u8>,
+    /// Comment character; lines starting with it are skipped when parsing.
+    pub comment: Option<u8>,
+    /// Whether the delimiter is a user-supplied candidate outside `DELIMITERS`
+    /// (scored with a neutral prior instead of the unknown-delimiter penalty).
+    pub custom_delimiter: bool,
 }
 
 impl PotentialDialect {
@@ -18,48 +28,45 @@ impl PotentialDialect {
     pub const fn new(delimiter: u8, quote: Quote, line_terminator: LineTerminator) -> Self {
         Self {
             delimiter,
+            separator: Separator::Byte(delimiter),
             quote,
             line_terminator,
+            escape: None,
+            comment: None,
+            custom_delimiter: false,
         }
     }
-}
 
-/// Line terminator sequences.
-#[allow(clippy::upper_case_acronyms)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum LineTerminator {
-    /// Unix-style line ending (\n).
-    LF,
-    /// Windows-style line ending (\r\n).
-    CRLF,
-    /// Old Mac-style line ending (\r).
-    CR,
-}
+    /// Set the escape character used inside quoted fields.
+    pub const fn with_escape(mut self, escape: Option<u8>) -> Self {
+        self.escape = escape;
+        self
+    }
 
-impl LineTerminator {
-    /// Returns the byte sequence for this line terminator.
-    #[allow(dead_code)]
-    pub const fn as_bytes(&self) -> &'static [u8] {
-        match self {
-            LineTerminator::LF => b"\n",
-            LineTerminator::CRLF => b"\r\n",
-            LineTerminator::CR => b"\r",
-        }
+    /// Set the separator the data was translated from (`delimiter` is then
+    /// its stand-in byte).
+    pub fn with_separator(mut self, separator: Separator) -> Self {
+        self.separator = separator;
+        self
     }
 
-    /// Returns the string representation.
-    #[allow(dead_code)]
-    pub const fn as_str(&self) -> &'static str {
-        match self {
-            LineTerminator::LF => "\\n",
-            LineTerminator::CRLF => "\\r\\n",
-            LineTerminator::CR => "\\r",
-        }
+    /// Mark the delimiter as a user-supplied candidate.
+    pub const fn with_custom_delimiter(mut self, custom_delimiter: bool) -> Self {
+        self.custom_delimiter = custom_delimiter;
+        self
+    }
+
+    /// Set the comment character.
+    pub const fn with_comment(mut self, comment: Option<u8>) -> Self {
+        self.comment = comment;
+        self
     }
 }
 
 /// Common delimiters to test (ordered by frequency in real-world data).
 /// Note: Colon is intentionally excluded as it commonly appears in time values (HH:MM:SS).
+/// Non-ASCII separators such as `§` are found by character frequency instead
+/// (see `separator::detect_unicode_separators`).
 // pub const DELIMITERS: &[u8] = &[
 //     b',',  // Comma (most common)
 //     b';',  // Semicolon (common in European locales)
@@ -71,7 +78,7 @@ impl LineTerminator {
 //     b'#',  // Hash (rare)car
 //     b'&',  // Ampersand (rare)
 // ];
-pub const DELIMITERS: &[u8] = b",;\t| ^~#&\xa7/";
+pub const DELIMITERS: &[u8] = b",;\t| ^~#&/";
 
 /// Quote characters to test.
 pub const QUOTES: &[Quote] = &[
@@ -137,15 +144,89 @@ pub fn detect_line_terminator(data: &[u8]) -> LineTerminator {
     }
 }
 
+/// Detect the line terminator style actually used by records.
+///
+/// Unlike `detect_line_terminator`, which picks the dominant style for parsing,
+/// this reports `LineTerminator::Mixed` when records end with more than one
+/// style. Line breaks inside quoted fields are skipped, so multi-line values
+/// written with a different terminator do not make a file look mixed.
+pub fn detect_line_terminator_style(
+    data: &[u8],
+    quote: Quote,
+    escape: Option<u8>,
+) -> LineTerminator {
+    let quote_char = match quote {
+        Quote::Some(q) => Some(q),
+        Quote::None => None,
+    };
+
+    let mut crlf_count = 0;
+    let mut lf_count = 0;
+    let mut cr_count = 0;
+    let mut in_quotes = false;
+
+    let mut i = 0;
+    while i < data.len() {
+        let byte = data[i];
+        if in_quotes && escape == Some(byte) {
+            i += 2;
+            continue;
+        }
+        if Some(byte) == quote_char {
+            in_quotes = !in_quotes;
+        } else if !in_quotes {
+            if byte == b'\r' {
+                if data.get(i + 1) == Some(&b'\n') {
+                    crlf_count += 1;
+                    i += 2;
+                    continue;
+                }
+                cr_count += 1;
+            } else if byte == b'\n' {
+                lf_count += 1;
+            }
+        }
+        i += 1;
+    }
+
+    let styles_seen = [crlf_count, lf_count, cr_count]
+        .iter()
+        .filter(|&&c| c > 0)
+        .count();
+    if styles_seen > 1 {
+        LineTerminator::Mixed
+    } else if crlf_count > 0 {
+        LineTerminator::CRLF
+    } else if cr_count > 0 {
+        LineTerminator::CR
+    } else {
+        LineTerminator::LF
+    }
+}
+
 /// Generate potential dialects with a detected line terminator.
 ///
 /// This reduces the search space by detecting the line terminator first.
 pub fn generate_dialects_with_terminator(line_terminator: LineTerminator) -> Vec<PotentialDialect> {
-    let mut dialects = Vec::with_capacity(DELIMITERS.len() * QUOTES.len());
+    generate_dialects_for_delimiters(DELIMITERS, line_terminator)
+}
 
-    for &delimiter in DELIMITERS {
+/// Generate potential dialects for a caller-supplied set of delimiters.
+///
+/// Delimiters outside `DELIMITERS` are marked as custom candidates.
+pub fn generate_dialects_for_delimiters(
+    delimiters: &[u8],
+    line_terminator: LineTerminator,
+) -> Vec<PotentialDialect> {
+    let mut dialects = Vec::with_capacity(delimiters.len() * QUOTES.len());
+
+    for &delimiter in delimiters {
+        let custom = !DELIMITERS.contains(&delimiter);
         for &quote in QUOTES {
-            dialects.push(PotentialDialect::new(delimiter, quote, line_terminator));
+            dialects.push(
+                PotentialDialect::new(delimiter, quote, line_terminator)
+                    .with_custom_delimiter(custom),
+            );
         }
     }
 
@@ -155,6 +236,7 @@ pub fn generate_dialects_with_terminator(line_terminator: LineTerminator) -> Vec
 /// Normalize line endings to LF for consistent parsing.
 ///
 /// Returns `Cow::Borrowed` for LF data (zero-copy) and `Cow::Owned` for CR/CRLF.
+/// `Mixed` rewrites both CRLF and lone CR to LF.
 /// This is used to normalize data once before scoring multiple dialects.
 pub fn normalize_line_endings(
     data: &[u8],
@@ -187,6 +269,25 @@ pub fn normalize_line_endings(
                     .collect(),
             )
         }
+        LineTerminator::Mixed => {
+            // Replace \r\n and standalone \r with \n
+            let mut result = Vec::with_capacity(data.len());
+            let mut i = 0;
+            while i < data.len() {
+                if data[i] == b'\r' {
+                    result.push(b'\n');
+                    i += if data.get(i + 1) == Some(&b'\n') {
+                        2
+                    } else {
+                        1
+                    };
+                } else {
+                    result.push(data[i]);
+                    i += 1;
+                }
+            }
+            Cow::Owned(result)
+        }
     }
 }
 
@@ -197,7 +298,7 @@ mod tests {
     #[test]
     fn test_generate_potential_dialects() {
         let dialects = generate_potential_dialects();
-        assert_eq!(dialects.len(), 99); // 11 * 3 * 3
+        assert_eq!(dialects.len(), 90); // 10 * 3 * 3
     }
 
     #[test]
@@ -209,4 +310,32 @@ mod tests {
         );
         assert_eq!(detect_line_terminator(b"a,b\rc,d\r"), LineTerminator::CR);
     }
+
+    #[test]
+    fn test_detect_line_terminator_style() {
+        let quote = Quote::Some(b'"');
+        assert_eq!(
+            detect_line_terminator_style(b"a,b\r\nc,d\r\n", quote, None),
+            LineTerminator::CRLF
+        );
+        assert_eq!(
+            detect_line_terminator_style(b"a,b\r\nc,d\ne,f\r\n", quote, None),
+            LineTerminator::Mixed
+        );
+        // A bare LF inside a quoted field is part of the value, not a record end
+        assert_eq!(
+            detect_line_terminator_style(b"a,b\r\n\"x\ny\",d\r\n", quote, None),
+            LineTerminator::CRLF
+        );
+        assert_eq!(
+            detect_line_terminator_style(b"a,b\r\n\"x\ny\",d\r\n", Quote::None, None),
+            LineTerminator::Mixed
+        );
+    }
+
+    #[test]
+    fn test_normalize_mixed_line_endings() {
+        let normalized = normalize_line_endings(b"a\r\nb\nc\rd", LineTerminator::Mixed);
+        assert_eq!(normalized.as_ref(), b"a\nb\nc\nd");
+    }
 }
@@ -68,6 +68,21 @@ pub static DATETIME_GENERAL_PATTERN: std::sync::LazyLock<Regex> = std::sync::Laz
         .expect("Invalid general datetime pattern")
 });
 
+/// Capturing pattern for the date part of a date or datetime: three numeric
+/// components and the first separator.
+pub static DATE_PARTS_PATTERN: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
+    Regex::new(r"^(\d{1,4})([-/\.])(\d{1,2})[-/\.](\d{1,4})").expect("Invalid date parts pattern")
+});
+
+/// Capturing pattern for the time part of a datetime: hour, minute, optional
+/// second and fraction, then an AM/PM marker, `Z` or a numeric UTC offset.
+pub static TIME_PARTS_PATTERN: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
+    Regex::new(
+        r"^(\d{1,2}):(\d{2})(?::(\d{2}))?(?:\.(\d+))?\s*(?:(AM|PM|am|pm)|(Z)|([+-])(\d{2}):?(\d{2}))?$",
+    )
+    .expect("Invalid time parts pattern")
+});
+
 /// Pattern for time values (HH:MM:SS).
 pub static TIME_PATTERN: std::sync::LazyLock<Regex> = std::sync::LazyLock::new(|| {
     Regex::new(r"^\d{1,2}:\d{2}(:\d{2})?(\.\d+)?(\s*(AM|PM|am|pm))?$")
@@ -270,6 +285,21 @@ mod tests {
         assert!(DATETIME_ISO_PATTERN.is_match("2023-12-31T12:30:45+05:30"));
     }
 
+    #[test]
+    fn test_date_time_parts_patterns() {
+        let caps = DATE_PARTS_PATTERN.captures("31.12.2023 10:00").unwrap();
+        assert_eq!(
+            (&caps[1], &caps[2], &caps[3], &caps[4]),
+            ("31", ".", "12", "2023")
+        );
+        let caps = TIME_PARTS_PATTERN.captures("12:30:45.5+05:30").unwrap();
+        assert_eq!(
+            (&caps[3], &caps[4], &caps[7], &caps[9]),
+            ("45", "5", "+", "30")
+        );
+        assert!(TIME_PARTS_PATTERN.is_match("9:05 PM"));
+    }
+
     #[test]
     fn test_null_pattern() {
         assert!(NULL_PATTERN.is_match("NULL"));
@@ -7,10 +7,15 @@ use std::cell::RefCell;
 
 use rayon::prelude::*;
 
-use super::potential_dialects::PotentialDialect;
+use super::escape::EscapeCounts;
+use super::potential_dialects::{LineTerminator, PotentialDialect};
 use super::table::{Table, parse_table, parse_table_normalized};
 use super::type_detection::{TypeScoreBuffers, calculate_pattern_score, calculate_type_score};
 use super::uniformity::{calculate_tau_0, calculate_tau_1, is_uniform};
+use crate::metadata::{
+    CandidateExplanation, DialectCandidate, Explanation, ScoreFactor, Separator,
+};
+use crate::scoring::{DEFAULT_SCORING, ScoringConfig};
 
 thread_local! {
     // Each rayon worker thread owns one reusable TypeScoreBuffers.  Vec::clear()
@@ -215,42 +220,49 @@ pub struct DialectScore {
     /// The combined gamma score (higher is better).
     pub gamma: f64,
     /// Consistency score (tau_0).
-    #[allow(dead_code)]
     pub tau_0: f64,
     /// Dispersion score (tau_1).
-    #[allow(dead_code)]
     pub tau_1: f64,
     /// Type detection score.
-    #[allow(dead_code)]
     pub type_score: f64,
     /// Pattern specificity score.
-    #[allow(dead_code)]
     pub pattern_score: f64,
     /// Number of rows parsed.
-    #[allow(dead_code)]
     pub num_rows: usize,
     /// Modal (most common) field count.
     pub num_fields: usize,
     /// Whether the table has uniform field counts.
     pub is_uniform: bool,
+    /// Every term, bonus and penalty that contributed to `gamma`; only
+    /// collected when an explanation was requested.
+    pub factors: Option<Vec<ScoreFactor>>,
 }
 
 impl DialectScore {
-    /// Create a new score result.
-    pub fn new(dialect: PotentialDialect, table: &Table, type_score: f64) -> Self {
+    /// Create a new score result, recording its factors if `explain` is set.
+    pub fn new(
+        dialect: PotentialDialect,
+        table: &Table,
+        type_score: f64,
+        config: &ScoringConfig,
+        explain: bool,
+    ) -> Self {
         let tau_0 = calculate_tau_0(table);
         let tau_1 = calculate_tau_1(table);
         let pattern_score = calculate_pattern_score(table);
         let uniform = is_uniform(table);
 
         // Calculate combined gamma score (includes delimiter penalty)
+        let mut factors = explain.then(Vec::new);
         let gamma = compute_gamma(
             tau_0,
             tau_1,
             type_score,
             pattern_score,
             table,
-            dialect.delimiter,
+            &dialect,
+            config,
+            factors.as_mut(),
         );
 
         Self {
@@ -263,6 +275,7 @@ impl DialectScore {
             num_rows: table.num_rows(),
             num_fields: table.modal_field_count(),
             is_uniform: uniform,
+            factors,
         }
     }
 
@@ -278,6 +291,42 @@ impl DialectScore {
             num_rows: 0,
             num_fields: 0,
             is_uniform: false,
+            factors: None,
+        }
+    }
+
+    /// Record a factor applied after `new`, if factors are being collected.
+    fn record(&mut self, factor: ScoreFactor) {
+        if let Some(factors) = &mut self.factors {
+            factors.push(factor);
+        }
+    }
+
+    /// Convert into the public candidate representation.
+    pub fn to_candidate(&self) -> DialectCandidate {
+        DialectCandidate {
+            delimiter: self.dialect.delimiter,
+            separator: self.dialect.separator.clone(),
+            quote: self.dialect.quote,
+            gamma: self.gamma,
+            tau_0: self.tau_0,
+            tau_1: self.tau_1,
+            type_score: self.type_score,
+            pattern_score: self.pattern_score,
+            num_rows: self.num_rows,
+            num_fields: self.num_fields,
+            is_uniform: self.is_uniform,
+        }
+    }
+
+    /// Convert into the public per-candidate scoring trace.
+    pub fn to_explanation(&self) -> CandidateExplanation {
+        CandidateExplanation {
+            delimiter: self.dialect.delimiter,
+            separator: self.dialect.separator.clone(),
+            quote: self.dialect.quote,
+            gamma: self.gamma,
+            factors: self.factors.clone().unwrap_or_default(),
         }
     }
 }
@@ -290,47 +339,72 @@ impl DialectScore {
 /// - type_score: higher means better type consistency
 /// - pattern_score: higher means more specific patterns detected
 /// - Additional bonuses for uniform tables and reasonable field counts
-/// - Penalties for uncommon delimiters
+/// - Penalties for uncommon delimiters (user-supplied candidates get a neutral prior)
+///
+/// Every additive term and every non-neutral multiplier is recorded in
+/// `factors` when given.
+#[allow(clippy::too_many_arguments)]
 fn compute_gamma(
     tau_0: f64,
     tau_1: f64,
     type_score: f64,
     pattern_score: f64,
     table: &Table,
-    delimiter: u8,
+    dialect: &PotentialDialect,
+    config: &ScoringConfig,
+    factors: Option<&mut Vec<ScoreFactor>>,
 ) -> f64 {
     if table.is_empty() {
         return 0.0;
     }
+    // Multi-character separators take the prior of their highest-priority character
+    let delimiter = match &dialect.separator {
+        Separator::Sequence(bytes) => config
+            .sequence_delimiter(bytes)
+            .unwrap_or(dialect.delimiter),
+        // Whitespace runs share the space prior
+        Separator::Whitespace => b' ',
+        _ => dialect.delimiter,
+    };
 
     // Base score from uniformity metrics
     let uniformity_score = (tau_0 * tau_1).sqrt();
 
     // Type detection contributes to the score
-    let type_contribution = type_score * 0.3;
+    let type_contribution = type_score * config.type_weight;
 
     // Pattern specificity provides additional signal
-    let pattern_contribution = pattern_score * 0.1;
+    let pattern_contribution = pattern_score * config.pattern_weight;
 
-    // Bonus for having multiple rows (more data is more reliable)
-    let row_bonus = (table.num_rows().min(20) as f64 / 20.0) * 0.1;
+    // Bonus for having multiple rows (more data is more reliable). The
+    // thresholds are public fields, so a zero is treated as 1 rather than
+    // dividing by it.
+    let row_bonus_rows = config.row_bonus_rows.max(1);
+    let row_bonus =
+        (table.num_rows().min(row_bonus_rows) as f64 / row_bonus_rows as f64) * config.row_bonus;
 
     // Bonus for having multiple fields (single field might be wrong delimiter)
     let field_count = table.modal_field_count();
+    let field_bonus_fields = config.field_bonus_fields.max(1);
     let field_bonus = if field_count >= 2 {
-        (field_count.min(10) as f64 / 10.0) * 0.2
+        (field_count.min(field_bonus_fields) as f64 / field_bonus_fields as f64)
+            * config.field_bonus
     } else {
         0.0
     };
 
     // Penalty for single-field tables (likely wrong delimiter)
-    let single_field_penalty = if field_count == 1 { 0.5 } else { 1.0 };
+    let single_field_penalty = if field_count == 1 {
+        config.single_field_penalty
+    } else {
+        1.0
+    };
 
     // Penalty for extremely high field counts (might be splitting on wrong char)
     let high_field_penalty = if field_count > 100 {
-        0.5
+        config.very_wide_table_penalty
     } else if field_count > 50 {
-        0.8
+        config.wide_table_penalty
     } else {
         1.0
     };
@@ -338,21 +412,21 @@ fn compute_gamma(
     // Penalty for very small samples (less reliable detection)
     let num_rows = table.num_rows();
     let small_sample_penalty = if num_rows < 3 {
-        0.80 // Very small - high unreliability
+        config.tiny_sample_penalty // Very small - high unreliability
     } else if num_rows < 5 {
-        0.90 // Small - moderate unreliability
+        config.small_sample_penalty // Small - moderate unreliability
     } else {
         1.0
     };
 
     // Penalty for uncommon delimiters
     // This helps prevent rare characters from winning due to accidental patterns
+    let mut delimiter_reason = "uncommon delimiter";
     let delimiter_penalty = match delimiter {
-        b',' | b';' | b'\t' => 1.0, // Common delimiters - no penalty
-        b'|' => 0.98,               // Pipe - slight penalty
-        b':' => 0.90,               // Colon - moderate penalty (often in timestamps)
-        b' ' => 0.75,               // Space - significant penalty (often in text)
-        b'^' | b'~' => 0.80,        // Rare delimiters
+        // Section sign (§) - rare but legitimate delimiter
+        _ if dialect.separator == Separator::Char('§') => config.section_sign_penalty,
+        // Other Unicode separators found by character frequency - unknown
+        _ if matches!(dialect.separator, Separator::Char(_)) => config.unicode_separator_penalty,
         // Hash - often a comment marker, but can be a legitimate delimiter.
         // For large uniform tables with ≥3 fields, reduce the penalty: the
         // heavy evidence of consistent multi-field parsing overrides the prior.
@@ -364,29 +438,107 @@ fn compute_gamma(
         //   - num_rows >= 50: small tables may accidentally produce consistent patterns
         //     even with `#` as a comment character.  50 rows provides enough statistical
         //     weight to trust the uniformity signal.
-        b'#' => {
-            if field_count >= 3 && num_rows >= 50 {
-                0.85 // Relaxed: large multi-field table is unlikely to be a comment file
-            } else {
-                0.60 // Strict default: treat `#` as a comment marker unless proven otherwise
-            }
+        b'#' if field_count >= 3 && num_rows >= 50 => {
+            delimiter_reason = "hash delimiter, relaxed for a large multi-field table";
+            config.hash_relaxed_penalty
         }
-        b'&' => 0.60, // Ampersand - very rare
-        0xA7 => 0.78, // Section sign (§) - rare but legitimate delimiter
-        b'/' => 0.65, // Forward slash - rare, often in paths/dates
-        _ => 0.70,    // Unknown - penalty
+        _ => match config.delimiter_penalties.get(&delimiter) {
+            Some(&penalty) => penalty,
+            // User-supplied candidate - neutral prior
+            None if dialect.custom_delimiter => config.custom_delimiter_penalty,
+            None => config.unknown_delimiter_penalty,
+        },
     };
 
     // Combine all factors
-    // uniformity_score * 0.5 + type_contribution + pattern_contribution + row_bonus + field_bonus;
-    let raw_score = uniformity_score.mul_add(0.5, type_contribution)
+    // uniformity_score * uniformity_weight + type_contribution + pattern_contribution
+    //     + row_bonus + field_bonus;
+    let raw_score = uniformity_score.mul_add(config.uniformity_weight, type_contribution)
         + pattern_contribution
         + row_bonus
         + field_bonus;
 
+    if let Some(factors) = factors {
+        factors.push(ScoreFactor::additive(
+            "uniformity",
+            uniformity_score * config.uniformity_weight,
+            "uniformity_weight x sqrt(tau_0 x tau_1)",
+        ));
+        factors.push(ScoreFactor::additive(
+            "type_score",
+            type_contribution,
+            "type_weight x per-column type consistency",
+        ));
+        factors.push(ScoreFactor::additive(
+            "pattern_score",
+            pattern_contribution,
+            "pattern_weight x type pattern specificity",
+        ));
+        factors.push(ScoreFactor::additive(
+            "row_bonus",
+            row_bonus,
+            "more rows make the sample more reliable (max at row_bonus_rows)",
+        ));
+        factors.push(ScoreFactor::additive(
+            "field_bonus",
+            field_bonus,
+            "multiple fields per row (max at field_bonus_fields)",
+        ));
+        if single_field_penalty != 1.0 {
+            factors.push(ScoreFactor::multiplier(
+                "single_field_penalty",
+                single_field_penalty,
+                "modal field count is 1, delimiter likely wrong",
+            ));
+        }
+        if high_field_penalty != 1.0 {
+            factors.push(ScoreFactor::multiplier(
+                "high_field_penalty",
+                high_field_penalty,
+                if field_count > 100 {
+                    "more than 100 fields, likely splitting on the wrong character"
+                } else {
+                    "more than 50 fields, likely splitting on the wrong character"
+                },
+            ));
+        }
+        if delimiter_penalty != 1.0 {
+            factors.push(ScoreFactor::multiplier(
+                "delimiter_penalty",
+                delimiter_penalty,
+                delimiter_reason,
+            ));
+        }
+        if small_sample_penalty != 1.0 {
+            factors.push(ScoreFactor::multiplier(
+                "small_sample_penalty",
+                small_sample_penalty,
+                if num_rows < 3 {
+                    "fewer than 3 rows"
+                } else {
+                    "fewer than 5 rows"
+                },
+            ));
+        }
+    }
+
     raw_score * single_field_penalty * high_field_penalty * delimiter_penalty * small_sample_penalty
 }
 
+/// Score a table split at fixed character positions instead of on a
+/// delimiter, as if it had been split on space (the closest delimited
+/// analogue, including its prior).
+pub fn score_fixed_width_table(table: &Table, config: &ScoringConfig) -> DialectScore {
+    use crate::metadata::Quote;
+
+    let dialect = PotentialDialect::new(b' ', Quote::None, LineTerminator::LF);
+    if table.is_empty() {
+        return DialectScore::zero(dialect);
+    }
+    let type_score = BUFFERS.with(|b| calculate_type_score(table, &mut b.borrow_mut()));
+    DialectScore::new(dialect, table, type_score, config, false)
+}
+
 /// Score a dialect against the data.
 ///
 /// Returns the DialectScore which includes the gamma score and component scores.
@@ -394,8 +546,15 @@ fn compute_gamma(
 pub fn score_dialect(data: &[u8], dialect: &PotentialDialect, max_rows: usize) -> DialectScore {
     let quote_counts = QuoteCounts::new(data);
     let mut buffers = TypeScoreBuffers::new();
-    let (score, _table) =
-        score_dialect_with_counts(data, dialect, max_rows, &quote_counts, &mut buffers);
+    let (score, _table) = score_dialect_with_counts(
+        data,
+        dialect,
+        max_rows,
+        &quote_counts,
+        &mut buffers,
+        &DEFAULT_SCORING,
+        false,
+    );
     score
 }
 
@@ -410,6 +569,8 @@ fn score_dialect_with_counts(
     max_rows: usize,
     quote_counts: &QuoteCounts,
     buffers: &mut TypeScoreBuffers,
+    config: &ScoringConfig,
+    explain: bool,
 ) -> (DialectScore, Table) {
     let table = parse_table(data, dialect, max_rows);
 
@@ -418,11 +579,19 @@ fn score_dialect_with_counts(
     }
 
     let type_score = calculate_type_score(&table, buffers);
-    let mut score = DialectScore::new(dialect.clone(), &table, type_score);
+    let mut score = DialectScore::new(dialect.clone(), &table, type_score, config, explain);
 
     // Apply quote evidence scoring using pre-computed counts and raw data for boundary detection
-    let quote_multiplier = quote_evidence_score_with_data(data, quote_counts, dialect);
+    let (quote_multiplier, quote_reason) =
+        quote_evidence_score_with_data(data, quote_counts, dialect);
     score.gamma *= quote_multiplier;
+    if quote_multiplier != 1.0 {
+        score.record(ScoreFactor::multiplier(
+            "quote_evidence",
+            quote_multiplier,
+            quote_reason,
+        ));
+    }
 
     (score, table)
 }
@@ -431,6 +600,7 @@ fn score_dialect_with_counts(
 ///
 /// This variant assumes the data has already been normalized to LF line endings
 /// for better performance when scoring multiple dialects.
+#[allow(clippy::too_many_arguments)]
 fn score_dialect_with_normalized_data(
     normalized_data: &[u8],
     dialect: &PotentialDialect,
@@ -438,6 +608,8 @@ fn score_dialect_with_normalized_data(
     quote_counts: &QuoteCounts,
     boundary_counts: &QuoteBoundaryCounts,
     buffers: &mut TypeScoreBuffers,
+    config: &ScoringConfig,
+    explain: bool,
 ) -> (DialectScore, Table) {
     let table = parse_table_normalized(normalized_data, dialect, max_rows);
 
@@ -446,11 +618,18 @@ fn score_dialect_with_normalized_data(
     }
 
     let type_score = calculate_type_score(&table, buffers);
-    let mut score = DialectScore::new(dialect.clone(), &table, type_score);
+    let mut score = DialectScore::new(dialect.clone(), &table, type_score, config, explain);
 
     // Apply quote evidence scoring using pre-computed counts and cached boundary counts
-    let quote_multiplier =
+    let (quote_multiplier, quote_reason) =
         quote_evidence_score_with_cached_boundaries(quote_counts, boundary_counts, dialect);
+    if quote_multiplier != 1.0 {
+        score.record(ScoreFactor::multiplier(
+            "quote_evidence",
+            quote_multiplier,
+            quote_reason,
+        ));
+    }
 
     // Dampen the quote boost when the first row has just 1 field AND the non-modal rows
     // exhibit diverse field counts (≥3 distinct values). This prevents JSON-content-in-
@@ -486,7 +665,14 @@ fn score_dialect_with_normalized_data(
                     // ≥3 distinct non-modal field counts → genuinely chaotic table, not just
                     // a small preamble. Scale boost down to 30% of excess so the correct
                     // dialect can compete.
-                    1.0 + (quote_multiplier - 1.0) * 0.3
+                    let dampened = 1.0 + (quote_multiplier - 1.0) * 0.3;
+                    score.record(ScoreFactor::multiplier(
+                        "quote_chaos_dampening",
+                        dampened / quote_multiplier,
+                        "quote boost scaled to 30% of its excess: first row has 1 field and \
+                         non-modal rows have 3+ distinct field counts",
+                    ));
+                    dampened
                 } else {
                     quote_multiplier
                 }
@@ -525,7 +711,20 @@ fn score_dialect_with_normalized_data(
             //   - 0.55: empirically calibrated to suppress the space-delimiter score
             //     below the true delimiter without zeroing it out entirely.  Values
             //     below ~0.50 caused regressions on legitimate space-delimited files.
-            effective_multiplier.min(1.05) * 0.55
+            let capped = effective_multiplier.min(1.05);
+            if capped != effective_multiplier {
+                score.record(ScoreFactor::multiplier(
+                    "space_quote_cap",
+                    capped / effective_multiplier,
+                    "quote boost capped at 1.05: most rows have an empty first field",
+                ));
+            }
+            score.record(ScoreFactor::multiplier(
+                "space_empty_first_field",
+                0.55,
+                "space delimiter where most rows have an empty first field",
+            ));
+            capped * 0.55
         } else {
             effective_multiplier
         }
@@ -562,6 +761,11 @@ fn score_dialect_with_normalized_data(
             //     factored in, without being so severe that it causes regressions on
             //     legitimate comma-separated files with rare embedded ' # '.
             score.gamma *= 0.82;
+            score.record(ScoreFactor::multiplier(
+                "comma_hash_separator",
+                0.82,
+                "' # ' appears in the first field of 90%+ rows, '#' is likely the delimiter",
+            ));
         }
     }
 
@@ -687,7 +891,7 @@ fn compute_single_quote_multiplier(
     single_density: usize,
     double_density: usize,
     min_density_threshold: usize,
-) -> f64 {
+) -> (f64, &'static str) {
     if quote_counts.double == 0
         && opening_count >= 2
         && boundary_count >= 4
@@ -695,17 +899,23 @@ fn compute_single_quote_multiplier(
     {
         // No double quotes, opening+closing boundaries, high density
         // This is strong evidence of single-quote quoting
-        2.2
+        (
+            2.2,
+            "single quotes at opening and closing field boundaries, no double quotes",
+        )
     } else if quote_counts.double == 0
         && opening_count >= 1
         && boundary_count >= 2
         && single_density >= min_density_threshold
     {
         // No double quotes, opening boundary present, decent density
-        1.20
+        (
+            1.20,
+            "single quotes at an opening field boundary, no double quotes",
+        )
     } else if double_density >= min_density_threshold {
         // Double quotes present - penalize single-quote detection
-        0.90
+        (0.90, "double quotes dominate")
     } else if quote_counts.backslash_single > 0
         && quote_counts.backslash_double == 0
         && boundary_count == 0
@@ -717,7 +927,10 @@ fn compute_single_quote_multiplier(
         // `backslash_double` is used only as a negative guard: double-quoted files
         // don't need this boost because their `\"` pairs already produce sufficient
         // boundary events via the normal path above.
-        1.10
+        (
+            1.10,
+            "backslash-escaped single quotes, no double-quote escapes",
+        )
     } else if quote_counts.double == 0
         && opening_count == 0
         && boundary_count >= 20
@@ -743,13 +956,16 @@ fn compute_single_quote_multiplier(
         //     that prose text with incidental apostrophes typically does not reach.
         //     Together, both conditions make false positives from apostrophe-heavy
         //     plain text extremely unlikely.
-        1.10
+        (1.10, "dense single quotes at closing field boundaries only")
     } else if boundary_count == 0 && single_density > 0 {
         // Single quotes in content but not at any boundaries (no opening,
         // no closing).  Likely just apostrophes in text content.
-        0.95
+        (
+            0.95,
+            "single quotes only inside field content (apostrophes)",
+        )
     } else {
-        1.0
+        (1.0, "no single-quote evidence")
     }
 }
 
@@ -759,11 +975,11 @@ fn quote_evidence_score_with_cached_boundaries(
     quote_counts: &QuoteCounts,
     boundary_counts: &QuoteBoundaryCounts,
     dialect: &PotentialDialect,
-) -> f64 {
+) -> (f64, &'static str) {
     use crate::metadata::Quote;
 
     if quote_counts.data_len == 0 {
-        return 1.0;
+        return (1.0, "no data");
     }
 
     // Calculate density (quotes per 1000 bytes) - higher density suggests quoting
@@ -783,16 +999,16 @@ fn quote_evidence_score_with_cached_boundaries(
             {
                 // No single quotes AND double quotes at boundaries with real density
                 // This handles small files with quoted fields containing delimiters
-                2.2
+                (2.2, "double quotes at field boundaries, no single quotes")
             } else if boundary_count >= 2 && double_density >= min_density_threshold {
                 // Double quotes at boundaries with good density
-                1.15
+                (1.15, "double quotes at field boundaries")
             } else if double_density >= min_density_threshold {
                 // Double quotes have significant density - moderate boost
-                1.08
+                (1.08, "significant double-quote density")
             } else {
                 // Neutral - rely on other scoring factors
-                1.0
+                (1.0, "no double-quote evidence")
             }
         }
         Quote::Some(b'\'') => {
@@ -815,12 +1031,12 @@ fn quote_evidence_score_with_cached_boundaries(
         Quote::None => {
             // Only penalize Quote::None when there's strong quoting evidence
             if double_density >= min_density_threshold {
-                0.90
+                (0.90, "double quotes present but quoting disabled")
             } else {
-                1.0
+                (1.0, "no quoting evidence")
             }
         }
-        Quote::Some(_) => 1.0, // Other quote chars - neutral
+        Quote::Some(_) => (1.0, "no evidence for this quote character"), // Other quote chars - neutral
     }
 }
 
@@ -848,11 +1064,11 @@ fn quote_evidence_score_with_data(
     data: &[u8],
     quote_counts: &QuoteCounts,
     dialect: &PotentialDialect,
-) -> f64 {
+) -> (f64, &'static str) {
     use crate::metadata::Quote;
 
     if quote_counts.data_len == 0 {
-        return 1.0;
+        return (1.0, "no data");
     }
 
     // Calculate density (quotes per 1000 bytes) - higher density suggests quoting
@@ -872,16 +1088,16 @@ fn quote_evidence_score_with_data(
             {
                 // No single quotes AND double quotes at boundaries with real density
                 // This handles small files with quoted fields containing delimiters
-                2.2
+                (2.2, "double quotes at field boundaries, no single quotes")
             } else if boundary_count >= 2 && double_density >= min_density_threshold {
                 // Double quotes at boundaries with good density
-                1.15
+                (1.15, "double quotes at field boundaries")
             } else if double_density >= min_density_threshold {
                 // Double quotes have significant density - moderate boost
-                1.08
+                (1.08, "significant double-quote density")
             } else {
                 // Neutral - rely on other scoring factors
-                1.0
+                (1.0, "no double-quote evidence")
             }
         }
         Quote::Some(b'\'') => {
@@ -903,12 +1119,12 @@ fn quote_evidence_score_with_data(
         Quote::None => {
             // Only penalize Quote::None when there's strong quoting evidence
             if double_density >= min_density_threshold {
-                0.90
+                (0.90, "double quotes present but quoting disabled")
             } else {
-                1.0
+                (1.0, "no quoting evidence")
             }
         }
-        Quote::Some(_) => 1.0, // Other quote chars - neutral
+        Quote::Some(_) => (1.0, "no evidence for this quote character"), // Other quote chars - neutral
     }
 }
 
@@ -918,7 +1134,12 @@ fn quote_evidence_score_with_data(
 /// 1. Common delimiters (comma, semicolon, tab) over rare ones (space, #, &)
 /// 2. Dialects with Quote::Some(b'"') over Quote::None (standard default)
 /// 3. Dialects with Quote::Some(b'"') over Quote::Some(b'\'')
-pub fn find_best_dialect(scores: &[DialectScore]) -> Option<&DialectScore> {
+///
+/// Priorities and the tie-break band (`tie_break_ratio`) come from `config`.
+pub fn find_best_dialect<'a>(
+    scores: &'a [DialectScore],
+    config: &ScoringConfig,
+) -> Option<&'a DialectScore> {
     // First, check if all dialects result in single-field tables
     // In that case, prefer comma as the default delimiter
     let all_single_field = scores
@@ -927,7 +1148,8 @@ pub fn find_best_dialect(scores: &[DialectScore]) -> Option<&DialectScore> {
         .all(|s| s.num_fields <= 1);
 
     scores.iter().filter(|s| s.gamma > 0.0).max_by(|a, b| {
-        // If scores are very close (within 5%, score_ratio > 0.95), use delimiter and quote preference
+        // If scores are very close (within 5% by default, score_ratio > tie_break_ratio), use
+        // delimiter and quote preference
         let score_ratio = if a.gamma > b.gamma {
             b.gamma / a.gamma
         } else {
@@ -936,30 +1158,30 @@ pub fn find_best_dialect(scores: &[DialectScore]) -> Option<&DialectScore> {
 
         // For single-field tables, prefer comma delimiter and double-quote
         if all_single_field {
-            let a_delim_priority = delimiter_priority(a.dialect.delimiter);
-            let b_delim_priority = delimiter_priority(b.dialect.delimiter);
+            let a_delim_priority = config.separator_priority(&a.dialect.separator);
+            let b_delim_priority = config.separator_priority(&b.dialect.separator);
 
             match a_delim_priority.cmp(&b_delim_priority) {
                 std::cmp::Ordering::Equal => {
                     // Same delimiter priority, use quote preference
-                    let a_quote_priority = quote_priority(a.dialect.quote);
-                    let b_quote_priority = quote_priority(b.dialect.quote);
+                    let a_quote_priority = config.quote_priority(a.dialect.quote);
+                    let b_quote_priority = config.quote_priority(b.dialect.quote);
                     return a_quote_priority.cmp(&b_quote_priority);
                 }
                 other => return other,
             }
         }
 
-        if score_ratio > 0.95 {
+        if score_ratio > config.tie_break_ratio {
             // Scores are close, use delimiter priority first, then quote priority
-            let a_delim_priority = delimiter_priority(a.dialect.delimiter);
-            let b_delim_priority = delimiter_priority(b.dialect.delimiter);
+            let a_delim_priority = config.separator_priority(&a.dialect.separator);
+            let b_delim_priority = config.separator_priority(&b.dialect.separator);
 
             match a_delim_priority.cmp(&b_delim_priority) {
                 std::cmp::Ordering::Equal => {
                     // Delimiters have same priority, check quotes
-                    let a_quote_priority = quote_priority(a.dialect.quote);
-                    let b_quote_priority = quote_priority(b.dialect.quote);
+                    let a_quote_priority = config.quote_priority(a.dialect.quote);
+                    let b_quote_priority = config.quote_priority(b.dialect.quote);
 
                     match a_quote_priority.cmp(&b_quote_priority) {
                         std::cmp::Ordering::Equal => a
@@ -980,38 +1202,135 @@ pub fn find_best_dialect(scores: &[DialectScore]) -> Option<&DialectScore> {
     })
 }
 
-/// Returns a priority score for delimiters (higher = preferred).
-/// Common delimiters like comma are preferred over rare ones like space or &.
-const fn delimiter_priority(delimiter: u8) -> u8 {
-    match delimiter {
-        b',' => 10, // Comma - most common, highest priority
-        b';' => 9,  // Semicolon - common in European locales
-        b'\t' => 8, // Tab - TSV files
-        // Pipe - common in data exports; intentionally tied with tab (both are
-        // respectable standard delimiters); tie resolved by iteration order
-        b'|' => 8,
-        b':' => 4, // Colon - sometimes used, but also appears in timestamps
-        b'^' => 3, // Caret - rare
-        b'~' => 3, // Tilde - rare
-        0xA7 => 2, // Section sign (§) - rare
-        b'/' => 2, // Forward slash - rare
-        b' ' => 2, // Space - very rare as delimiter, often appears in text
-        b'#' => 1, // Hash - very rare, often used for comments
-        b'&' => 1, // Ampersand - very rare
-        _ => 0,    // Unknown delimiters - lowest priority
+/// Rank scored dialects for reporting: the selected dialect first, followed by
+/// the remaining candidates in descending gamma order.
+///
+/// `scores` must already be sorted by descending gamma (as returned by
+/// `score_all_dialects_with_best_table`), and `best` must point into it.
+pub fn rank_candidates(scores: &[DialectScore], best: &DialectScore) -> Vec<DialectCandidate> {
+    let mut ranked = Vec::with_capacity(scores.len());
+    ranked.push(best.to_candidate());
+    ranked.extend(
+        scores
+            .iter()
+            .filter(|s| !std::ptr::eq(*s, best))
+            .map(DialectScore::to_candidate),
+    );
+    ranked
+}
+
+/// Build the scoring trace for all candidates, ordered like `rank_candidates`.
+pub fn explain_candidates(
+    scores: &[DialectScore],
+    best: &DialectScore,
+    config: &ScoringConfig,
+) -> Explanation {
+    let mut candidates = Vec::with_capacity(scores.len());
+    candidates.push(best.to_explanation());
+    candidates.extend(
+        scores
+            .iter()
+            .filter(|s| !std::ptr::eq(*s, best))
+            .map(DialectScore::to_explanation),
+    );
+
+    Explanation {
+        candidates,
+        tie_break: decided_by_tie_break(scores, best, config),
+    }
+}
+
+/// Compute a confidence in [0, 1] for the selected dialect.
+///
+/// Confidence is derived from the gamma margin between the best dialect and
+/// the strongest runner-up that would parse the data differently. Quote
+/// variants of the winning delimiter that produce an identical table are not
+/// counted as competitors. The value is halved when the decision fell into the
+/// tie-break band or every candidate parsed to a single column, since in both
+/// cases the winner was chosen by priority rather than by score.
+pub fn dialect_confidence(
+    scores: &[DialectScore],
+    best: &DialectScore,
+    config: &ScoringConfig,
+) -> f64 {
+    if best.gamma <= 0.0 {
+        return 0.0;
+    }
+
+    let ratio = runner_up_ratio(scores, best);
+    let mut confidence = 1.0 - ratio.powi(8);
+    if decided_by_tie_break(scores, best, config) {
+        confidence *= 0.5;
+    }
+
+    confidence.clamp(0.0, 1.0)
+}
+
+/// Whether `find_best_dialect` picked `best` on delimiter/quote priority rather
+/// than on a clear gamma margin.
+pub fn decided_by_tie_break(
+    scores: &[DialectScore],
+    best: &DialectScore,
+    config: &ScoringConfig,
+) -> bool {
+    let all_single_field = scores
+        .iter()
+        .filter(|s| s.gamma > 0.0)
+        .all(|s| s.num_fields <= 1);
+    all_single_field || runner_up_ratio(scores, best) > config.tie_break_ratio
+}
+
+/// Gamma of the strongest competing dialect relative to `best`, capped at 1.0.
+fn runner_up_ratio(scores: &[DialectScore], best: &DialectScore) -> f64 {
+    if best.gamma <= 0.0 {
+        return 1.0;
     }
+
+    let runner_up = scores
+        .iter()
+        .filter(|s| s.gamma > 0.0 && !std::ptr::eq(*s, best) && parses_differently(s, best))
+        .map(|s| s.gamma)
+        .fold(0.0_f64, f64::max);
+
+    (runner_up / best.gamma).min(1.0)
+}
+
+/// Whether two scored dialects yield a materially different table.
+fn parses_differently(a: &DialectScore, b: &DialectScore) -> bool {
+    a.dialect.separator != b.dialect.separator
+        || a.num_fields != b.num_fields
+        || a.num_rows != b.num_rows
+        || (a.tau_0 - b.tau_0).abs() > f64::EPSILON
+        || (a.tau_1 - b.tau_1).abs() > f64::EPSILON
+        || (a.type_score - b.type_score).abs() > f64::EPSILON
 }
 
-/// Returns a priority score for quote characters (higher = preferred).
-/// Double-quote is the standard default and should be preferred.
-const fn quote_priority(quote: crate::metadata::Quote) -> u8 {
+/// Return the dialect with its escape character set from the data.
+///
+/// The quote-aware escape scan only runs when a backslash-quote pair for the
+/// dialect's quote character exists somewhere in the sample; otherwise the
+/// dialect keeps RFC 4180 doubled-quote escaping.
+fn with_detected_escape(
+    data: &[u8],
+    dialect: &PotentialDialect,
+    quote_counts: &QuoteCounts,
+) -> PotentialDialect {
     use crate::metadata::Quote;
-    match quote {
-        Quote::Some(b'"') => 3,  // Standard default - highest priority
-        Quote::Some(b'\'') => 2, // Single quote - second priority
-        Quote::None => 1,        // No quoting - lowest priority
-        Quote::Some(_) => 0,     // Other quote chars - very low priority
+
+    let backslash_pairs = match dialect.quote {
+        Quote::Some(b'"') => quote_counts.backslash_double,
+        Quote::Some(b'\'') => quote_counts.backslash_single,
+        _ => 0,
+    };
+    if backslash_pairs == 0 || dialect.escape.is_some() {
+        return dialect.clone();
     }
+
+    let Quote::Some(quote) = dialect.quote else {
+        return dialect.clone();
+    };
+    let escape = EscapeCounts::new(data, dialect.delimiter, quote).escape();
+    dialect.clone().with_escape(escape)
 }
 
 /// Score all potential dialects and return sorted by gamma score (descending).
@@ -1021,7 +1340,8 @@ pub fn score_all_dialects(
     dialects: &[PotentialDialect],
     max_rows: usize,
 ) -> Vec<DialectScore> {
-    let (scores, _) = score_all_dialects_with_best_table(data, dialects, max_rows);
+    let (scores, _) =
+        score_all_dialects_with_best_table(data, dialects, max_rows, &DEFAULT_SCORING, false);
     scores
 }
 
@@ -1029,11 +1349,14 @@ pub fn score_all_dialects(
 /// along with the parsed table of the best-scoring dialect.
 ///
 /// This avoids re-parsing the best dialect's data for preamble detection
-/// and metadata building.
+/// and metadata building. Score factors are only collected when `explain` is
+/// set.
 pub fn score_all_dialects_with_best_table(
     data: &[u8],
     dialects: &[PotentialDialect],
     max_rows: usize,
+    config: &ScoringConfig,
+    explain: bool,
 ) -> (Vec<DialectScore>, Option<Table>) {
     // Pre-compute quote counts once for all dialect evaluations
     let quote_counts = QuoteCounts::new(data);
@@ -1063,14 +1386,17 @@ pub fn score_all_dialects_with_best_table(
     let pairs: Vec<(DialectScore, Table)> = dialects
         .par_iter()
         .map(|d| {
+            let d = with_detected_escape(normalized_bytes, d, &quote_counts);
             BUFFERS.with(|b| {
                 score_dialect_with_normalized_data(
                     normalized_bytes,
-                    d,
+                    &d,
                     max_rows,
                     &quote_counts,
                     &boundary_counts,
                     &mut b.borrow_mut(),
+                    config,
+                    explain,
                 )
             })
         })
@@ -1107,7 +1433,7 @@ pub fn score_all_dialects_with_best_table(
 mod tests {
     use super::*;
     use crate::metadata::Quote;
-    use crate::tum::potential_dialects::LineTerminator;
+    use crate::tum::potential_dialects::{LineTerminator, generate_dialects_with_terminator};
 
     #[test]
     fn test_score_simple_csv() {
@@ -1143,11 +1469,67 @@ mod tests {
         ];
 
         let scores = score_all_dialects(data, &dialects, 100);
-        let best = find_best_dialect(&scores).unwrap();
+        let best = find_best_dialect(&scores, &DEFAULT_SCORING).unwrap();
 
         assert_eq!(best.dialect.delimiter, b',');
     }
 
+    #[test]
+    fn test_factors_reproduce_gamma() {
+        let data = b"name,note\n\"Smith, J\",\"a, b\"\n\"Doe, A\",\"c, d\"\n";
+        let dialects = generate_dialects_with_terminator(LineTerminator::LF);
+        let (scores, _) =
+            score_all_dialects_with_best_table(data, &dialects, 100, &DEFAULT_SCORING, true);
+
+        for score in scores.iter().filter(|s| s.gamma > 0.0) {
+            let factors = score.factors.as_ref().unwrap();
+            let raw: f64 = factors
+                .iter()
+                .filter(|f| f.kind == crate::metadata::FactorKind::Additive)
+                .map(|f| f.value)
+                .sum();
+            let multiplied = factors
+                .iter()
+                .filter(|f| f.kind == crate::metadata::FactorKind::Multiplier)
+                .fold(raw, |acc, f| acc * f.value);
+            assert!(
+                (multiplied - score.gamma).abs() < 1e-9,
+                "factors for {:?} give {multiplied}, gamma is {}",
+                score.dialect,
+                score.gamma
+            );
+        }
+
+        let best = find_best_dialect(&scores, &DEFAULT_SCORING).unwrap();
+        let factors = best.factors.as_ref().unwrap();
+        assert!(factors.iter().any(|f| f.name == "quote_evidence"));
+    }
+
+    #[test]
+    fn test_dialect_confidence_clear_winner() {
+        let data = b"id,name,score\n1,alpha,3.5\n2,beta,4.0\n3,gamma,2.5\n4,delta,1.0\n";
+        let dialects = generate_dialects_with_terminator(LineTerminator::LF);
+        let scores = score_all_dialects(data, &dialects, 100);
+        let best = find_best_dialect(&scores, &DEFAULT_SCORING).unwrap();
+
+        let confidence = dialect_confidence(&scores, best, &DEFAULT_SCORING);
+        assert!(confidence > 0.5, "confidence was {confidence}");
+        assert!(confidence <= 1.0);
+    }
+
+    #[test]
+    fn test_dialect_confidence_tie_break_is_low() {
+        // Single column: every delimiter yields the same table, so the winner
+        // is decided by priority alone.
+        let data = b"value\nalpha\nbeta\ngamma\n";
+        let dialects = generate_dialects_with_terminator(LineTerminator::LF);
+        let scores = score_all_dialects(data, &dialects, 100);
+        let best = find_best_dialect(&scores, &DEFAULT_SCORING).unwrap();
+
+        let confidence = dialect_confidence(&scores, best, &DEFAULT_SCORING);
+        assert!(confidence <= 0.5, "confidence was {confidence}");
+    }
+
     // --- Tests for quote_opening_boundary_count and get_single_opening_boundary_count ---
 
     #[test]
@@ -0,0 +1,608 @@
+//! Multi-byte field separators.
+//!
+//! `csv` only splits on a single byte, so a multi-byte separator is replaced
+//! by a stand-in byte that does not occur in the data before parsing. The
+//! replacement follows `csv`'s quoting rules: separators inside quoted fields
+//! are left alone.
+
+use std::collections::HashMap;
+
+use crate::metadata::{Quote, Separator};
+use crate::tum::potential_dialects::DELIMITERS;
+
+/// Stand-in bytes tried, in order, for a multi-byte separator (ASCII control
+/// characters that rarely occur in text).
+const PLACEHOLDERS: &[u8] = b"\x1f\x1e\x1d\x1c\x1b\x1a\x19\x18\x17\x16\x15\x14\x13\x12\x11\x10";
+
+/// Maximum number of non-empty lines examined for Unicode separators.
+const MAX_SAMPLE_LINES: usize = 200;
+
+/// Maximum number of Unicode separator candidates returned.
+const MAX_UNICODE_SEPARATORS: usize = 3;
+
+/// Maximum number of multi-character separator candidates returned.
+const MAX_SEQUENCES: usize = 3;
+
+/// Longest multi-character separator considered, in bytes.
+const MAX_SEQUENCE_LEN: usize = 4;
+
+/// Minimum share of lines on which a character (or sequence) must occur with
+/// its modal count to be a separator candidate.
+const MIN_CONSISTENT_SHARE: f64 = 0.8;
+
+/// Pick a stand-in byte for a multi-byte separator that does not occur in
+/// `data`.
+pub fn choose_placeholder(data: &[u8]) -> Option<u8> {
+    let mut seen = [false; 256];
+    for &b in data {
+        seen[b as usize] = true;
+    }
+    PLACEHOLDERS.iter().copied().find(|&b| !seen[b as usize])
+}
+
+/// Find non-ASCII characters that occur the same number of times on most
+/// lines of `data`, ordered by how consistently they do.
+///
+/// Letters, digits, whitespace and control characters are never candidates.
+/// ASCII-only data returns at once.
+pub fn detect_unicode_separators(data: &[u8]) -> Vec<char> {
+    if data.is_ascii() {
+        return Vec::new();
+    }
+    let text = String::from_utf8_lossy(data);
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .take(MAX_SAMPLE_LINES)
+        .collect();
+    if lines.len() < 2 {
+        return Vec::new();
+    }
+
+    // Per-line occurrence counts of every candidate character
+    let mut counts: HashMap<char, Vec<usize>> = HashMap::new();
+    for (i, line) in lines.iter().enumerate() {
+        for c in line.chars().filter(|&c| is_separator_candidate(c)) {
+            counts.entry(c).or_insert_with(|| vec![0; lines.len()])[i] += 1;
+        }
+    }
+
+    let mut candidates: Vec<(char, f64)> = counts
+        .into_iter()
+        .filter_map(|(c, per_line)| {
+            let (_, share) = modal_count(&per_line)?;
+            (share >= MIN_CONSISTENT_SHARE).then_some((c, share))
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    candidates.truncate(MAX_UNICODE_SEPARATORS);
+    candidates.into_iter().map(|(c, _)| c).collect()
+}
+
+/// Find runs of 2 to `MAX_SEQUENCE_LEN` punctuation characters or tabs (e.g.
+/// `~|~`, `::`) that occur the same number of times on most lines of `data`,
+/// ordered by how consistently they do.
+///
+/// On the lines counted, the characters of the sequence must not occur
+/// outside it, which also drops partial matches such as `~|` in `~|~`. Quote
+/// characters never take part in a sequence, and neither do repeats of a
+/// built-in delimiter (`,,`, `||`, two tabs), which are read as that delimiter
+/// with empty columns.
+pub fn detect_multi_char_separators(data: &[u8]) -> Vec<Vec<u8>> {
+    let lines: Vec<&[u8]> = data
+        .split(|&b| b == b'\n' || b == b'\r')
+        .filter(|line| !line.trim_ascii().is_empty())
+        .take(MAX_SAMPLE_LINES)
+        .collect();
+    if lines.len() < 2 || !has_sequence_pairs(&lines) {
+        return Vec::new();
+    }
+
+    // Per-line non-overlapping occurrence counts of every candidate sequence
+    let mut counts: HashMap<&[u8], Vec<usize>> = HashMap::new();
+    for (i, line) in lines.iter().enumerate() {
+        for len in 2..=MAX_SEQUENCE_LEN {
+            let mut next_start: HashMap<&[u8], usize> = HashMap::new();
+            for (start, gram) in line.windows(len).enumerate() {
+                if !gram.iter().all(|&b| is_sequence_byte(b))
+                    || !rejected_alone(gram)
+                    || next_start.get(gram).is_some_and(|&next| start < next)
+                {
+                    continue;
+                }
+                next_start.insert(gram, start + len);
+                counts.entry(gram).or_insert_with(|| vec![0; lines.len()])[i] += 1;
+            }
+        }
+    }
+
+    let mut candidates: Vec<(&[u8], f64)> = counts
+        .into_iter()
+        .filter_map(|(gram, per_line)| {
+            let (count, _) = modal_count(&per_line)?;
+            // Only lines where the characters of the sequence never occur on
+            // their own count, so `,,` is no candidate in `a,,b,c`
+            let exclusive_lines = lines
+                .iter()
+                .zip(&per_line)
+                .filter(|&(line, &c)| c == count && only_in_sequence(line, gram, count))
+                .count();
+            let share = exclusive_lines as f64 / lines.len() as f64;
+            (share >= MIN_CONSISTENT_SHARE).then_some((gram, share))
+        })
+        .collect();
+    candidates.sort_by(|a, b| {
+        b.1.total_cmp(&a.1)
+            .then(b.0.len().cmp(&a.0.len()))
+            .then(a.0.cmp(b.0))
+    });
+    candidates.truncate(MAX_SEQUENCES);
+    candidates
+        .into_iter()
+        .map(|(gram, _)| gram.to_vec())
+        .collect()
+}
+
+/// Whether most lines of `data` are padded with runs of blanks, as in
+/// aligned text tables, making a `Separator::Whitespace` candidate worth
+/// scoring.
+///
+/// A line counts when it starts with a space or has a run of two or more
+/// spaces or tabs that includes a space; tab-only runs are left to TSV.
+pub fn detect_whitespace_runs(data: &[u8]) -> bool {
+    let lines: Vec<&[u8]> = data
+        .split(|&b| b == b'\n' || b == b'\r')
+        .filter(|line| !line.trim_ascii().is_empty())
+        .take(MAX_SAMPLE_LINES)
+        .collect();
+    if lines.len() < 2 {
+        return false;
+    }
+
+    let padded = lines
+        .iter()
+        .filter(|line| {
+            let line = line.trim_ascii_end();
+            line.first() == Some(&b' ')
+                || line
+                    .windows(2)
+                    .any(|pair| pair.iter().all(|&b| is_blank(b)) && pair.contains(&b' '))
+        })
+        .count();
+    padded as f64 / lines.len() as f64 >= MIN_CONSISTENT_SHARE
+}
+
+/// Whether `b` is a space or a tab.
+fn is_blank(b: u8) -> bool {
+    b == b' ' || b == b'\t'
+}
+
+/// The most common non-zero per-line count and the share of lines that have
+/// it, or `None` if the counts are all zero.
+fn modal_count(per_line: &[usize]) -> Option<(usize, f64)> {
+    let mut frequency: HashMap<usize, usize> = HashMap::new();
+    for &count in per_line.iter().filter(|&&count| count > 0) {
+        *frequency.entry(count).or_default() += 1;
+    }
+    let (count, lines) = frequency
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))?;
+    Some((count, lines as f64 / per_line.len() as f64))
+}
+
+/// Whether `b` can be part of a multi-character separator.
+fn is_sequence_byte(b: u8) -> bool {
+    b == b'\t' || (b.is_ascii_punctuation() && b != b'"' && b != b'\'')
+}
+
+/// Whether enough lines have two adjacent characters that could start a
+/// sequence no single delimiter explains, a cheap test that every candidate
+/// of `detect_multi_char_separators` passes.
+fn has_sequence_pairs(lines: &[&[u8]]) -> bool {
+    let with_pair = lines
+        .iter()
+        .filter(|line| {
+            line.windows(2)
+                .any(|pair| pair.iter().all(|&b| is_sequence_byte(b)) && rejected_alone(pair))
+        })
+        .count();
+    with_pair as f64 / lines.len() as f64 >= MIN_CONSISTENT_SHARE
+}
+
+/// Whether no byte of `gram` makes a delimiter on its own: it is not a
+/// built-in delimiter, or splitting on it would leave the other characters of
+/// `gram` in the fields. A repeated delimiter such as `,,` fails this, since
+/// splitting on `,` gives the same table with empty columns.
+fn rejected_alone(gram: &[u8]) -> bool {
+    gram.iter()
+        .all(|&b| !DELIMITERS.contains(&b) || gram.iter().any(|&other| other != b))
+}
+
+/// Whether every occurrence of the characters of `gram` in `line` is part of
+/// one of its `count` occurrences.
+fn only_in_sequence(line: &[u8], gram: &[u8], count: usize) -> bool {
+    gram.iter().all(|&b| {
+        let in_gram = gram.iter().filter(|&&g| g == b).count();
+        bytecount::count(line, b) == count * in_gram
+    })
+}
+
+/// Whether `c` could be a Unicode field separator.
+fn is_separator_candidate(c: char) -> bool {
+    !c.is_ascii()
+        && !c.is_alphanumeric()
+        && !c.is_whitespace()
+        && !c.is_control()
+        && c != char::REPLACEMENT_CHARACTER
+}
+
+/// Replace every unquoted `separator` in `data` with `placeholder`.
+pub fn translate_separator(
+    data: &[u8],
+    separator: &Separator,
+    placeholder: u8,
+    quote: Quote,
+    escape: Option<u8>,
+) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    SeparatorTranslator::new(separator, placeholder, quote, escape).translate(data, &mut out, true);
+    out
+}
+
+/// Position within a record, tracked the way `csv` tracks quoting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldState {
+    /// At the start of a field, where a quote opens a quoted field.
+    Start,
+    /// Inside an unquoted field; quotes are literal.
+    Unquoted,
+    /// Inside a quoted field.
+    Quoted,
+    /// Just after a closing quote (a second quote is an escaped quote).
+    AfterQuote,
+}
+
+/// Incremental, quote-aware replacement of a multi-byte separator with a
+/// stand-in byte, for input that arrives in chunks.
+#[derive(Debug, Clone)]
+pub struct SeparatorTranslator {
+    separator: Vec<u8>,
+    /// Collapse runs of spaces and tabs (`Separator::Whitespace`).
+    whitespace: bool,
+    placeholder: u8,
+    quote: Option<u8>,
+    escape: Option<u8>,
+    state: FieldState,
+    /// Trailing bytes of the previous chunk that may begin a separator or an
+    /// escape sequence.
+    pending: Vec<u8>,
+}
+
+impl SeparatorTranslator {
+    /// Create a translator replacing `separator` with `placeholder`.
+    pub fn new(separator: &Separator, placeholder: u8, quote: Quote, escape: Option<u8>) -> Self {
+        Self {
+            separator: separator.to_bytes(),
+            whitespace: *separator == Separator::Whitespace,
+            placeholder,
+            quote: quote.char(),
+            escape,
+            state: FieldState::Start,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Translate the next chunk of `input`, appending the result to `out`.
+    ///
+    /// Unless `last` is set, bytes at the end of the chunk that could begin a
+    /// separator are held back until the next call.
+    pub fn translate(&mut self, input: &[u8], out: &mut Vec<u8>, last: bool) {
+        let mut buf = std::mem::take(&mut self.pending);
+        let data: &[u8] = if buf.is_empty() {
+            input
+        } else {
+            buf.extend_from_slice(input);
+            &buf
+        };
+        let consumed = self.translate_slice(data, out, last);
+        self.pending = data[consumed..].to_vec();
+    }
+
+    /// Translate `buf`, returning the number of bytes consumed.
+    fn translate_slice(&mut self, buf: &[u8], out: &mut Vec<u8>, last: bool) -> usize {
+        let mut i = 0;
+        while i < buf.len() {
+            let b = buf[i];
+            if self.state == FieldState::Quoted {
+                if self.escape == Some(b) {
+                    if i + 1 == buf.len() && !last {
+                        break;
+                    }
+                    let end = (i + 2).min(buf.len());
+                    out.extend_from_slice(&buf[i..end]);
+                    i = end;
+                    continue;
+                }
+                if self.quote == Some(b) {
+                    self.state = FieldState::AfterQuote;
+                }
+                out.push(b);
+                i += 1;
+                continue;
+            }
+
+            let rest = &buf[i..];
+            if self.whitespace && is_blank(b) {
+                let run = rest.iter().take_while(|&&b| is_blank(b)).count();
+                if run == rest.len() && !last {
+                    break;
+                }
+                // Blanks at the start or end of a line are dropped
+                let at_line_end = rest.get(run).is_none_or(|&b| b == b'\n' || b == b'\r');
+                if self.state != FieldState::Start && !at_line_end {
+                    out.push(self.placeholder);
+                    self.state = FieldState::Start;
+                }
+                i += run;
+                continue;
+            }
+            if rest.starts_with(&self.separator) {
+                out.push(self.placeholder);
+                self.state = FieldState::Start;
+                i += self.separator.len();
+                continue;
+            }
+            if !last && rest.len() < self.separator.len() && self.separator.starts_with(rest) {
+                break;
+            }
+
+            self.state = match (self.state, b) {
+                (_, b'\n' | b'\r') => FieldState::Start,
+                (FieldState::Start | FieldState::AfterQuote, _) if self.quote == Some(b) => {
+                    FieldState::Quoted
+                }
+                _ => FieldState::Unquoted,
+            };
+            out.push(b);
+            i += 1;
+        }
+        i
+    }
+}
+
+/// Incremental replacement of the stand-in byte in `csv::Writer` output with
+/// a multi-byte separator, undoing `SeparatorTranslator` for output.
+///
+/// `csv` only quotes fields that contain the stand-in, so unquoted fields
+/// that contain the separator (or could run into a neighbouring one) are
+/// quoted here. Without a quote character they are written as they are.
+#[derive(Debug, Clone)]
+pub struct SeparatorRestorer {
+    separator: Vec<u8>,
+    placeholder: u8,
+    quote: Option<u8>,
+    escape: Option<u8>,
+    /// Inside a quoted field.
+    quoted: bool,
+    /// The previous byte in a quoted field was the escape character.
+    escaped: bool,
+    /// The unquoted field written so far.
+    field: Vec<u8>,
+}
+
+impl SeparatorRestorer {
+    /// Create a restorer replacing `placeholder` with `separator`.
+    pub fn new(separator: &Separator, placeholder: u8, quote: Quote, escape: Option<u8>) -> Self {
+        Self {
+            separator: separator.to_bytes(),
+            placeholder,
+            quote: quote.char(),
+            escape,
+            quoted: false,
+            escaped: false,
+            field: Vec::new(),
+        }
+    }
+
+    /// Restore the next chunk of `input`, appending the result to `out`.
+    ///
+    /// The current unquoted field is held back until it ends; `last` ends it
+    /// with the input.
+    pub fn restore(&mut self, input: &[u8], out: &mut Vec<u8>, last: bool) {
+        for &b in input {
+            if self.quoted {
+                if self.escaped {
+                    self.escaped = false;
+                } else if self.escape == Some(b) {
+                    self.escaped = true;
+                } else if self.quote == Some(b) {
+                    // A doubled quote reopens the field on the next byte
+                    self.quoted = false;
+                }
+                out.push(b);
+            } else if self.quote == Some(b) {
+                // `csv` only writes quotes inside quoted fields
+                self.end_field(out);
+                self.quoted = true;
+                out.push(b);
+            } else if b == self.placeholder {
+                self.end_field(out);
+                out.extend_from_slice(&self.separator);
+            } else if b == b'\n' || b == b'\r' {
+                self.end_field(out);
+                out.push(b);
+            } else {
+                self.field.push(b);
+            }
+        }
+        if last {
+            self.end_field(out);
+        }
+    }
+
+    /// Write the held-back unquoted field, quoted if it needs to be.
+    fn end_field(&mut self, out: &mut Vec<u8>) {
+        match self.quote {
+            Some(quote) if self.needs_quotes() => {
+                out.push(quote);
+                out.extend_from_slice(&self.field);
+                out.push(quote);
+            }
+            _ => out.extend_from_slice(&self.field),
+        }
+        self.field.clear();
+    }
+
+    /// Whether the field contains the separator, or starts or ends with part
+    /// of it so that it would merge with an adjacent separator.
+    fn needs_quotes(&self) -> bool {
+        let field = &self.field[..];
+        let sep = &self.separator[..];
+        field.windows(sep.len()).any(|w| w == sep)
+            || (1..sep.len()).any(|n| field.ends_with(&sep[..n]) || field.starts_with(&sep[n..]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn translate(data: &str, c: char, quote: Quote) -> String {
+        let out = translate_separator(data.as_bytes(), &Separator::Char(c), b'\x1f', quote, None);
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_translate_separator_respects_quotes() {
+        assert_eq!(
+            translate("a§b\n\"x§y\"§z\n", '§', Quote::Some(b'"')),
+            "a\x1fb\n\"x§y\"\x1fz\n"
+        );
+        // Doubled quotes stay inside the quoted field
+        assert_eq!(
+            translate("\"a\"\"§b\"§c", '§', Quote::Some(b'"')),
+            "\"a\"\"§b\"\x1fc"
+        );
+        // A quote inside an unquoted field is literal
+        assert_eq!(translate("5\"§x", '§', Quote::Some(b'"')), "5\"\x1fx");
+        assert_eq!(translate("\"a§b\"", '§', Quote::None), "\"a\x1fb\"");
+    }
+
+    #[test]
+    fn test_restore_separator() {
+        let mut restorer =
+            SeparatorRestorer::new(&Separator::Char('§'), b'\x1f', Quote::Some(b'"'), None);
+        let mut out = Vec::new();
+        for chunk in [&b"a\x1f\"x\x1f"[..], b"y\"\"\"\x1fb", b"\xC2\xA7c\x1fz\n"] {
+            restorer.restore(chunk, &mut out, false);
+        }
+        restorer.restore(b"1\x1f2", &mut out, true);
+        assert_eq!(out, "a§\"x\x1fy\"\"\"§\"b§c\"§z\n1§2".as_bytes());
+
+        // Parts of a sequence at a field edge would merge with the separator
+        let mut restorer = SeparatorRestorer::new(
+            &Separator::Sequence(b"||".to_vec()),
+            b'\x1f',
+            Quote::Some(b'"'),
+            None,
+        );
+        let mut out = Vec::new();
+        restorer.restore(b"a|\x1fx|y\x1f|b\n", &mut out, true);
+        assert_eq!(out, b"\"a|\"||x|y||\"|b\"\n");
+    }
+
+    #[test]
+    fn test_translator_chunk_boundaries() {
+        let data = "a→b→\"c→d\"\n1→2→3\n".as_bytes();
+        let expected = translate_separator(
+            data,
+            &Separator::Char('→'),
+            b'\x1f',
+            Quote::Some(b'"'),
+            None,
+        );
+        for chunk_size in 1..5 {
+            let mut translator =
+                SeparatorTranslator::new(&Separator::Char('→'), b'\x1f', Quote::Some(b'"'), None);
+            let mut out = Vec::new();
+            for chunk in data.chunks(chunk_size) {
+                translator.translate(chunk, &mut out, false);
+            }
+            translator.translate(&[], &mut out, true);
+            assert_eq!(out, expected, "chunk size {chunk_size}");
+        }
+    }
+
+    #[test]
+    fn test_translate_whitespace_runs() {
+        let data = b"  PID TTY      CMD\n  12 pts/0 \t \"a  b\"  \n";
+        for chunk_size in [1, 2, 3, data.len()] {
+            let mut translator =
+                SeparatorTranslator::new(&Separator::Whitespace, b'\x1f', Quote::Some(b'"'), None);
+            let mut out = Vec::new();
+            for chunk in data.chunks(chunk_size) {
+                translator.translate(chunk, &mut out, false);
+            }
+            translator.translate(&[], &mut out, true);
+            assert_eq!(
+                out, b"PID\x1fTTY\x1fCMD\n12\x1fpts/0\x1f\"a  b\"\n",
+                "chunk size {chunk_size}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_detect_whitespace_runs() {
+        assert!(detect_whitespace_runs(
+            b"Filesystem  Size  Used\n/dev/sda1    50G   20G\ntmpfs       1.0G     0\n"
+        ));
+        assert!(!detect_whitespace_runs(b"a b c\n1 2 3\n4 5 6\n"));
+        // Empty TSV fields are not padding
+        assert!(!detect_whitespace_runs(b"a\t\tc\n1\t\t3\n"));
+    }
+
+    #[test]
+    fn test_detect_unicode_separators() {
+        let data = "name¦price¦note\nwidget¦5€¦ok\ngadget¦7€¦é\nthing¦9¦ok\n".as_bytes();
+        assert_eq!(detect_unicode_separators(data), vec!['¦']);
+
+        // Accented letters are never candidates
+        assert!(detect_unicode_separators("é,a\né,b\né,c\n".as_bytes()).is_empty());
+    }
+
+    #[test]
+    fn test_detect_multi_char_separators() {
+        let data = b"id~|~name~|~qty\n1~|~Ann~|~3\n2~|~Bo~|~4\n3~|~Cy~|~5\n";
+        assert_eq!(detect_multi_char_separators(data), vec![b"~|~".to_vec()]);
+
+        let data = b"id::name::qty\n1::Ann::3\n2::Bo::4\n";
+        assert_eq!(detect_multi_char_separators(data), vec![b"::".to_vec()]);
+
+        // Plain CSV has no pair of sequence characters to count
+        assert!(!has_sequence_pairs(&[b"a,b,1", b"c,d,2.5", b"e,f,-3"]));
+        assert!(has_sequence_pairs(&[b"a::b", b"c::d"]));
+
+        // A repeated delimiter is that delimiter with empty columns
+        for data in [
+            &b"a,,b,,c\n1,,2,,3\n4,,5,,6\n"[..],
+            b"id||name||qty\n1||Ann||3\n2||Bo||4\n",
+            b"a\t\tb\n1\t\t2\n3\t\t4\n",
+        ] {
+            assert!(detect_multi_char_separators(data).is_empty());
+        }
+
+        // Quotes are not part of a sequence
+        let data = b"\"a\",\"b\"\n\"c\",\"d\"\n";
+        assert!(detect_multi_char_separators(data).is_empty());
+
+        // An always-empty column is not a separator
+        let data = b"a,,b,c\n1,,2,x\n3,,4,y\n";
+        assert!(detect_multi_char_separators(data).is_empty());
+    }
+
+    #[test]
+    fn test_choose_placeholder() {
+        assert_eq!(choose_placeholder(b"a,b"), Some(b'\x1f'));
+        assert_eq!(choose_placeholder(b"a\x1fb"), Some(b'\x1e'));
+    }
+}
@@ -175,6 +175,7 @@ fn parse_table_impl<D: AsRef<[u8]>>(data: D, dialect: &PotentialDialect, max_row
         .delimiter(dialect.delimiter)
         .has_headers(false)
         .flexible(true)
+        .comment(dialect.comment)
         .buffer_capacity(32768); // 32KB buffer
 
     // Configure quoting
@@ -185,6 +186,9 @@ fn parse_table_impl<D: AsRef<[u8]>>(data: D, dialect: &PotentialDialect, max_row
         Quote::Some(q) => {
             reader_builder.quoting(true);
             reader_builder.quote(q);
+            if let Some(escape) = dialect.escape {
+                reader_builder.escape(Some(escape)).double_quote(false);
+            }
         }
     }
 
@@ -246,6 +250,9 @@ fn normalize_line_endings<'a>(data: &'a [u8], dialect: &PotentialDialect) -> Cow
                     .collect(),
             )
         }
+        LineTerminator::Mixed => {
+            super::potential_dialects::normalize_line_endings(data, LineTerminator::Mixed)
+        }
     }
 }
 
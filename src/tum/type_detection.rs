@@ -7,7 +7,7 @@ use crate::field_type::Type;
 /// Check for NULL-like values using string matching instead of regex.
 /// This is a hot path optimization - called for every cell.
 #[inline]
-fn is_null_value(s: &str) -> bool {
+pub(crate) fn is_null_value(s: &str) -> bool {
     matches!(
         s,
         "" | "-"
@@ -64,7 +64,7 @@ fn is_signed_int(s: &str) -> bool {
 /// Check for boolean values using exhaustive match instead of regex.
 /// This is a hot path optimization - called for every cell.
 #[inline]
-fn is_boolean(s: &str) -> bool {
+pub(crate) fn is_boolean(s: &str) -> bool {
     match s.len() {
         1 => {
             let b = s.as_bytes()[0].to_ascii_lowercase();
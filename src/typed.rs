@@ -0,0 +1,484 @@
+//! Typed values parsed with the same rules as type inference.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use crate::error::{CellError, Result};
+use crate::field_type::Type;
+use crate::metadata::Metadata;
+use crate::reader::SourceReader;
+use crate::sample::DatePreference;
+use crate::tum::regexes::{
+    DATE_EURO_PATTERN, DATE_ISO_PATTERN, DATE_PARTS_PATTERN, DATE_US_PATTERN,
+    DATETIME_GENERAL_PATTERN, DATETIME_ISO_PATTERN, FLOAT_PATTERN, FLOAT_THOUSANDS_PATTERN,
+    TIME_PARTS_PATTERN,
+};
+use crate::tum::type_detection::{is_boolean, is_null_value};
+
+/// A calendar date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    /// Year (two-digit years are mapped to 1970–2069).
+    pub year: i32,
+    /// Month (1–12).
+    pub month: u8,
+    /// Day of the month (1–31).
+    pub day: u8,
+}
+
+impl Date {
+    /// Create a date, returning `None` if the month or day is out of range.
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Self> {
+        let days_in_month = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+            2 => 28,
+            _ => return None,
+        };
+        (1..=days_in_month)
+            .contains(&day)
+            .then_some(Self { year, month, day })
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// A date with a time of day and an optional UTC offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DateTime {
+    /// The calendar date.
+    pub date: Date,
+    /// Hour (0–23).
+    pub hour: u8,
+    /// Minute (0–59).
+    pub minute: u8,
+    /// Second (0–60, allowing a leap second).
+    pub second: u8,
+    /// Fractional second in nanoseconds.
+    pub nanosecond: u32,
+    /// UTC offset in minutes (`Some(0)` for `Z`), or `None` for local time.
+    pub offset_minutes: Option<i16>,
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}T{:02}:{:02}:{:02}",
+            self.date, self.hour, self.minute, self.second
+        )?;
+        if self.nanosecond > 0 {
+            write!(f, ".{:09}", self.nanosecond)?;
+        }
+        match self.offset_minutes {
+            Some(0) => write!(f, "Z"),
+            Some(offset) => {
+                let sign = if offset < 0 { '-' } else { '+' };
+                let offset = offset.unsigned_abs();
+                write!(f, "{sign}{:02}:{:02}", offset / 60, offset % 60)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// A cell value parsed according to its column's inferred type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// An empty cell or NULL token (`NULL`, `N/A`, `-`, `#N/A`, …).
+    Null,
+    /// A boolean (`true`/`false`, `yes`/`no`, `y`/`n`, `t`/`f`, `on`/`off`, `1`/`0`).
+    Bool(bool),
+    /// An unsigned integer.
+    U64(u64),
+    /// A signed integer.
+    I64(i64),
+    /// A floating point number (thousands separators removed).
+    F64(f64),
+    /// A date.
+    Date(Date),
+    /// A date and time (date-only cells in a `DateTime` column are at midnight).
+    DateTime(DateTime),
+    /// Any other text, unchanged.
+    Text(String),
+}
+
+/// The values of one record, with a per-cell error for cells that do not
+/// parse as their column's type.
+pub type TypedRecord = Vec<std::result::Result<Value, CellError>>;
+
+/// Parse a cell as the given column type.
+///
+/// Uses the NULL token list, boolean vocabulary, number and date patterns
+/// of type inference. Columns of type `Text` or `NULL` accept any value.
+pub fn parse_value(
+    value: &str,
+    column_type: Type,
+    date_preference: DatePreference,
+) -> std::result::Result<Value, Type> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() || is_null_value(trimmed) {
+        return Ok(Value::Null);
+    }
+
+    let parsed = match column_type {
+        Type::NULL | Type::Text => Some(Value::Text(value.to_string())),
+        Type::Unsigned => trimmed
+            .strip_prefix('+')
+            .unwrap_or(trimmed)
+            .parse()
+            .ok()
+            .map(Value::U64),
+        Type::Signed => trimmed
+            .strip_prefix('+')
+            .unwrap_or(trimmed)
+            .parse()
+            .ok()
+            .map(Value::I64),
+        Type::Float => parse_float(trimmed).map(Value::F64),
+        Type::Boolean => parse_bool(trimmed).map(Value::Bool),
+        Type::Date => parse_date(trimmed, date_preference).map(Value::Date),
+        Type::DateTime => parse_datetime(trimmed, date_preference).map(Value::DateTime),
+    };
+    parsed.ok_or(column_type)
+}
+
+/// Parse a float, allowing US-style thousands separators.
+fn parse_float(s: &str) -> Option<f64> {
+    if FLOAT_PATTERN.is_match(s) {
+        return s.parse().ok();
+    }
+    if FLOAT_THOUSANDS_PATTERN.is_match(s) {
+        return s.replace(',', "").parse().ok();
+    }
+    None
+}
+
+/// Parse a value from the boolean vocabulary.
+fn parse_bool(s: &str) -> Option<bool> {
+    if !is_boolean(s) {
+        return None;
+    }
+    let truthy = ["1", "y", "t", "on", "yes", "true"];
+    Some(truthy.iter().any(|t| s.eq_ignore_ascii_case(t)))
+}
+
+/// Parse a date matching one of the date patterns.
+fn parse_date(s: &str, date_preference: DatePreference) -> Option<Date> {
+    if !(DATE_ISO_PATTERN.is_match(s)
+        || DATE_US_PATTERN.is_match(s)
+        || DATE_EURO_PATTERN.is_match(s))
+    {
+        return None;
+    }
+    let (date, rest) = parse_date_prefix(s, date_preference)?;
+    rest.is_empty().then_some(date)
+}
+
+/// Parse a datetime matching one of the datetime patterns, or a plain date
+/// (at midnight).
+fn parse_datetime(s: &str, date_preference: DatePreference) -> Option<DateTime> {
+    if !(DATETIME_ISO_PATTERN.is_match(s) || DATETIME_GENERAL_PATTERN.is_match(s)) {
+        return parse_date(s, date_preference).map(|date| DateTime {
+            date,
+            hour: 0,
+            minute: 0,
+            second: 0,
+            nanosecond: 0,
+            offset_minutes: None,
+        });
+    }
+
+    let (date, rest) = parse_date_prefix(s, date_preference)?;
+    let rest = rest.strip_prefix(['T', ' ']).unwrap_or(rest);
+    let caps = TIME_PARTS_PATTERN.captures(rest)?;
+    let number = |i: usize| {
+        caps.get(i)
+            .map_or(Some(0), |m| m.as_str().parse::<u8>().ok())
+    };
+
+    let mut hour = number(1)?;
+    let minute = number(2)?;
+    let second = number(3)?;
+    if let Some(meridiem) = caps.get(5) {
+        if !(1..=12).contains(&hour) {
+            return None;
+        }
+        hour %= 12;
+        if meridiem.as_str().eq_ignore_ascii_case("pm") {
+            hour += 12;
+        }
+    }
+    if hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    // Fractional seconds, truncated to nanoseconds
+    let nanosecond = caps.get(4).map_or(0, |m| {
+        let digits = &m.as_str()[..m.as_str().len().min(9)];
+        digits.parse::<u32>().unwrap_or(0) * 10u32.pow(9 - digits.len() as u32)
+    });
+
+    let offset_minutes = if caps.get(6).is_some() {
+        Some(0)
+    } else if let Some(sign) = caps.get(7) {
+        let hours: i16 = caps[8].parse().ok()?;
+        let minutes: i16 = caps[9].parse().ok()?;
+        let offset = hours * 60 + minutes;
+        Some(if sign.as_str() == "-" {
+            -offset
+        } else {
+            offset
+        })
+    } else {
+        None
+    };
+
+    Some(DateTime {
+        date,
+        hour,
+        minute,
+        second,
+        nanosecond,
+        offset_minutes,
+    })
+}
+
+/// Parse the date at the start of `s`, returning it with the remaining text.
+///
+/// A four-digit first component is year-month-day, a `.` separator is
+/// day.month.year, and otherwise `date_preference` decides between
+/// month/day/year and day/month/year (falling back to the other order when
+/// the preferred one is not a valid date).
+fn parse_date_prefix(s: &str, date_preference: DatePreference) -> Option<(Date, &str)> {
+    let caps = DATE_PARTS_PATTERN.captures(s)?;
+    let rest = &s[caps.get(0)?.end()..];
+    let first = &caps[1];
+    let a: u32 = first.parse().ok()?;
+    let b: u32 = caps[3].parse().ok()?;
+    let c: u32 = caps[4].parse().ok()?;
+
+    let make = |year: u32, year_digits: usize, month: u32, day: u32| {
+        let year = match (year_digits, year) {
+            (2, y) if y < 70 => 2000 + y,
+            (2, y) => 1900 + y,
+            (_, y) => y,
+        };
+        Date::new(
+            i32::try_from(year).ok()?,
+            u8::try_from(month).ok()?,
+            u8::try_from(day).ok()?,
+        )
+    };
+
+    let date = if first.len() == 4 {
+        make(a, 4, b, c)
+    } else {
+        let year_digits = caps[4].len();
+        let dmy = make(c, year_digits, b, a);
+        if &caps[2] == "." {
+            dmy
+        } else {
+            let mdy = make(c, year_digits, a, b);
+            if date_preference.is_dmy() {
+                dmy.or(mdy)
+            } else {
+                mdy.or(dmy)
+            }
+        }
+    }?;
+    Some((date, rest))
+}
+
+/// Iterates over records as typed values, using the inferred column types.
+///
+/// Created by [`Metadata::open_typed_reader`] or [`Metadata::typed_reader_from`].
+/// Cells beyond the inferred columns are returned as `Value::Text`.
+#[derive(Debug)]
+pub struct TypedReader<R> {
+    /// The underlying CSV reader.
+    reader: csv::Reader<R>,
+    /// Inferred type for each column.
+    types: Vec<Type>,
+    /// Order of ambiguous numeric dates.
+    date_preference: DatePreference,
+    /// Reused record buffer.
+    record: csv::StringRecord,
+}
+
+impl<R: Read> TypedReader<R> {
+    /// Create a typed reader over `reader` with the given column types.
+    pub fn new(reader: csv::Reader<R>, types: Vec<Type>) -> Self {
+        Self {
+            reader,
+            types,
+            date_preference: DatePreference::default(),
+            record: csv::StringRecord::new(),
+        }
+    }
+
+    /// Set the order used for ambiguous dates such as `03/04/2024`.
+    pub fn date_preference(&mut self, date_preference: DatePreference) -> &mut Self {
+        self.date_preference = date_preference;
+        self
+    }
+
+    /// The header record of the underlying reader.
+    pub fn headers(&mut self) -> Result<&csv::StringRecord> {
+        Ok(self.reader.headers()?)
+    }
+
+    /// The column types values are parsed as.
+    pub fn types(&self) -> &[Type] {
+        &self.types
+    }
+
+    /// Consume the typed reader, returning the underlying CSV reader.
+    pub fn into_inner(self) -> csv::Reader<R> {
+        self.reader
+    }
+}
+
+impl<R: Read> Iterator for TypedReader<R> {
+    type Item = Result<TypedRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.reader.read_record(&mut self.record) {
+            Ok(true) => Some(Ok(self
+                .record
+                .iter()
+                .enumerate()
+                .map(|(column, cell)| {
+                    let column_type = self.types.get(column).copied().unwrap_or(Type::Text);
+                    parse_value(cell, column_type, self.date_preference).map_err(|expected| {
+                        CellError {
+                            column,
+                            expected,
+                            value: cell.to_string(),
+                        }
+                    })
+                })
+                .collect())),
+            Ok(false) => None,
+            Err(e) => Some(Err(e.into())),
+        }
+    }
+}
+
+impl Metadata {
+    /// Open the file at `path` as a [`TypedReader`] over the data rows,
+    /// parsing ambiguous dates with the sniffer's `date_preference`.
+    pub fn open_typed_reader<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> Result<TypedReader<SourceReader<BufReader<File>>>> {
+        let mut reader = TypedReader::new(self.open_reader(path)?, self.types.clone());
+        reader.date_preference(self.date_preference);
+        Ok(reader)
+    }
+
+    /// Wrap `reader` (positioned at the start of the input) in a
+    /// [`TypedReader`] over the data rows.
+    pub fn typed_reader_from<R: Read>(&self, reader: R) -> Result<TypedReader<SourceReader<R>>> {
+        let mut reader = TypedReader::new(self.reader_from(reader)?, self.types.clone());
+        reader.date_preference(self.date_preference);
+        Ok(reader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: &str, column_type: Type) -> std::result::Result<Value, Type> {
+        parse_value(value, column_type, DatePreference::MdyFormat)
+    }
+
+    fn date(year: i32, month: u8, day: u8) -> Date {
+        Date::new(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_parse_scalars() {
+        assert_eq!(parse(" N/A ", Type::Unsigned), Ok(Value::Null));
+        assert_eq!(parse("+42", Type::Unsigned), Ok(Value::U64(42)));
+        assert_eq!(parse("-7", Type::Signed), Ok(Value::I64(-7)));
+        assert_eq!(parse("7", Type::Signed), Ok(Value::I64(7)));
+        assert_eq!(parse("1,234.5", Type::Float), Ok(Value::F64(1234.5)));
+        assert_eq!(parse("1e3", Type::Float), Ok(Value::F64(1000.0)));
+        assert_eq!(parse("Yes", Type::Boolean), Ok(Value::Bool(true)));
+        assert_eq!(parse("off", Type::Boolean), Ok(Value::Bool(false)));
+        assert_eq!(
+            parse(" hi ", Type::Text),
+            Ok(Value::Text(" hi ".to_string()))
+        );
+
+        assert_eq!(parse("abc", Type::Unsigned), Err(Type::Unsigned));
+        assert_eq!(
+            parse("99999999999999999999", Type::Signed),
+            Err(Type::Signed)
+        );
+        assert_eq!(parse("1,23", Type::Float), Err(Type::Float));
+        assert_eq!(parse("maybe", Type::Boolean), Err(Type::Boolean));
+    }
+
+    #[test]
+    fn test_parse_dates() {
+        assert_eq!(
+            parse("2024-02-29", Type::Date),
+            Ok(Value::Date(date(2024, 2, 29)))
+        );
+        assert_eq!(
+            parse("03/04/24", Type::Date),
+            Ok(Value::Date(date(2024, 3, 4)))
+        );
+        assert_eq!(
+            parse("31/12/1999", Type::Date),
+            Ok(Value::Date(date(1999, 12, 31)))
+        );
+        assert_eq!(
+            parse("01.02.2023", Type::Date),
+            Ok(Value::Date(date(2023, 2, 1)))
+        );
+        assert_eq!(
+            parse_value("03/04/2024", Type::Date, DatePreference::DmyFormat),
+            Ok(Value::Date(date(2024, 4, 3)))
+        );
+        assert_eq!(parse("2023-02-29", Type::Date), Err(Type::Date));
+        assert_eq!(parse("2023-02-01 10:00", Type::Date), Err(Type::Date));
+    }
+
+    #[test]
+    fn test_parse_datetimes() {
+        let Ok(Value::DateTime(dt)) = parse("2023-12-31T23:59:58.25+05:30", Type::DateTime) else {
+            panic!("expected a datetime");
+        };
+        assert_eq!(dt.date, date(2023, 12, 31));
+        assert_eq!((dt.hour, dt.minute, dt.second), (23, 59, 58));
+        assert_eq!(dt.nanosecond, 250_000_000);
+        assert_eq!(dt.offset_minutes, Some(330));
+        assert_eq!(dt.to_string(), "2023-12-31T23:59:58.250000000+05:30");
+
+        let Ok(Value::DateTime(dt)) = parse("12/31/2023 12:05 AM", Type::DateTime) else {
+            panic!("expected a datetime");
+        };
+        assert_eq!((dt.hour, dt.minute, dt.offset_minutes), (0, 5, None));
+
+        let Ok(Value::DateTime(dt)) = parse("2023-12-31", Type::DateTime) else {
+            panic!("expected a datetime");
+        };
+        assert_eq!(dt.to_string(), "2023-12-31T00:00:00");
+
+        assert_eq!(
+            parse("2023-12-31T25:00", Type::DateTime),
+            Err(Type::DateTime)
+        );
+    }
+}
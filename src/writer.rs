@@ -0,0 +1,341 @@
+//! Building `csv::Writer`s that re-emit a sniffed dialect.
+
+use std::io::{self, Write};
+
+use encoding_rs::{Encoder, EncoderResult, Encoding};
+
+use crate::error::Result;
+use crate::metadata::{Dialect, LineTerminator, Metadata, Quote, Separator};
+use crate::tum::separator::SeparatorRestorer;
+
+/// The UTF-8 byte order mark.
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+impl Dialect {
+    /// A `csv::WriterBuilder` with this dialect's delimiter, quote character,
+    /// escape style and line terminator.
+    ///
+    /// `Quote::None` never quotes fields, and `LineTerminator::Mixed` writes `\n`.
+    /// `csv` writes a single-byte delimiter, so a multi-byte separator is
+    /// written as its `delimiter` stand-in, and `Separator::Whitespace` as a
+    /// single space. [`Metadata::writer_from`] translates the stand-in back to
+    /// the separator.
+    pub fn writer_builder(&self) -> csv::WriterBuilder {
+        let delimiter = match self.separator {
+            Separator::Whitespace => b' ',
+            _ => self.delimiter,
+        };
+        let mut builder = csv::WriterBuilder::new();
+        builder
+            .delimiter(delimiter)
+            .has_headers(self.header.has_header_row)
+            .flexible(self.flexible)
+            .double_quote(self.double_quote)
+            .escape(self.escape.unwrap_or(b'\\'))
+            .terminator(match self.line_terminator {
+                LineTerminator::CRLF => csv::Terminator::CRLF,
+                LineTerminator::CR => csv::Terminator::Any(b'\r'),
+                LineTerminator::LF | LineTerminator::Mixed => csv::Terminator::Any(b'\n'),
+            });
+        match self.quote {
+            Quote::None => {
+                builder.quote_style(csv::QuoteStyle::Never);
+            }
+            Quote::Some(q) => {
+                builder.quote(q);
+            }
+        }
+        builder
+    }
+}
+
+impl Metadata {
+    /// A `csv::WriterBuilder` matching the detected dialect.
+    pub fn writer_builder(&self) -> csv::WriterBuilder {
+        self.dialect.writer_builder()
+    }
+
+    /// Wrap `writer` in a `csv::Writer` matching the detected dialect.
+    ///
+    /// Output is encoded back to the detected source encoding and a
+    /// multi-byte separator's `delimiter` stand-in is replaced by the
+    /// separator outside quoted fields (see [`SinkWriter`]). With
+    /// `keep_preamble`, the BOM (if the source had one) and the original
+    /// `preamble` lines are written first, so the output starts exactly like
+    /// the sniffed input.
+    ///
+    /// `Separator::Whitespace` output is lossy: fields are joined by a single
+    /// space, so alignment is lost and empty cells or cells containing spaces
+    /// do not read back as the same fields. Such output is not a round trip
+    /// of the input; pick a byte delimiter with `writer_builder` instead.
+    pub fn writer_from<W: Write>(
+        &self,
+        writer: W,
+        keep_preamble: bool,
+    ) -> Result<csv::Writer<SinkWriter<W>>> {
+        let mut sink = SinkWriter::new(writer, self.encoding.encoding);
+        if keep_preamble {
+            if self.encoding.has_bom {
+                sink.write_bom()?;
+            }
+            sink.write_all(&self.preamble)?;
+        }
+        let separator = &self.dialect.separator;
+        if separator.as_byte().is_none() && *separator != Separator::Whitespace {
+            sink.restorer = Some(SeparatorRestorer::new(
+                separator,
+                self.dialect.delimiter,
+                self.dialect.quote,
+                self.dialect.escape,
+            ));
+        }
+        Ok(self.writer_builder().from_writer(sink))
+    }
+}
+
+/// A writer that restores a multi-byte separator in the UTF-8 output of a
+/// `csv::Writer` and encodes it back to the source encoding.
+///
+/// Created by [`Metadata::writer_from`]. Characters the encoding cannot
+/// represent fail with `io::ErrorKind::InvalidData`. Call
+/// [`into_inner`](Self::into_inner) after `csv::Writer::into_inner` to end the
+/// output of stateful encodings such as ISO-2022-JP.
+pub struct SinkWriter<W: Write> {
+    /// The underlying writer.
+    inner: W,
+    /// How UTF-8 text is encoded for `inner`.
+    target: Target,
+    /// Replaces the `delimiter` stand-in with a multi-byte separator.
+    restorer: Option<SeparatorRestorer>,
+    /// Trailing bytes of a UTF-8 sequence split across writes.
+    pending: Vec<u8>,
+    /// Encoded bytes for `inner`.
+    out: Vec<u8>,
+}
+
+/// Output encoding of a `SinkWriter`.
+enum Target {
+    /// UTF-8 bytes are passed through.
+    Utf8,
+    /// UTF-16 code units, which `encoding_rs` does not encode.
+    Utf16 { big_endian: bool },
+    /// Any other encoding.
+    Encoder(Encoder),
+}
+
+impl<W: Write> SinkWriter<W> {
+    /// Wrap `inner`, encoding to `encoding`.
+    pub fn new(inner: W, encoding: &'static Encoding) -> Self {
+        let target = if encoding == encoding_rs::UTF_8 {
+            Target::Utf8
+        } else if encoding == encoding_rs::UTF_16LE || encoding == encoding_rs::UTF_16BE {
+            Target::Utf16 {
+                big_endian: encoding == encoding_rs::UTF_16BE,
+            }
+        } else {
+            Target::Encoder(encoding.new_encoder())
+        };
+        Self {
+            inner,
+            target,
+            restorer: None,
+            pending: Vec::new(),
+            out: Vec::new(),
+        }
+    }
+
+    /// Write the byte order mark of the target encoding, if it has one.
+    pub fn write_bom(&mut self) -> io::Result<()> {
+        let bom: &[u8] = match self.target {
+            Target::Utf8 => UTF8_BOM,
+            Target::Utf16 { big_endian: false } => b"\xFF\xFE",
+            Target::Utf16 { big_endian: true } => b"\xFE\xFF",
+            Target::Encoder(_) => return Ok(()),
+        };
+        self.inner.write_all(bom)
+    }
+
+    /// End the output and return the underlying writer.
+    ///
+    /// Fails if the last write ended inside a UTF-8 sequence.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        if let Some(restorer) = &mut self.restorer {
+            restorer.restore(&[], &mut self.pending, true);
+            self.write_pending()?;
+        }
+        if !self.pending.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "output ends with an incomplete UTF-8 sequence",
+            ));
+        }
+        self.encode("", true)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+
+    /// Encode and write the complete UTF-8 text in `pending`.
+    fn write_pending(&mut self) -> io::Result<()> {
+        if matches!(self.target, Target::Utf8) {
+            self.inner.write_all(&self.pending)?;
+            self.pending.clear();
+            return Ok(());
+        }
+
+        // Hold back a UTF-8 sequence cut off at the end of the last write
+        let complete = match std::str::from_utf8(&self.pending) {
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            _ => self.pending.len(),
+        };
+        let rest = self.pending.split_off(complete);
+        let text = String::from_utf8(std::mem::replace(&mut self.pending, rest))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        self.encode(&text, false)
+    }
+
+    /// Encode `text` and write it to `inner`.
+    fn encode(&mut self, text: &str, last: bool) -> io::Result<()> {
+        self.out.clear();
+        match &mut self.target {
+            Target::Utf8 => self.out.extend_from_slice(text.as_bytes()),
+            Target::Utf16 { big_endian } => {
+                for unit in text.encode_utf16() {
+                    let bytes = if *big_endian {
+                        unit.to_be_bytes()
+                    } else {
+                        unit.to_le_bytes()
+                    };
+                    self.out.extend_from_slice(&bytes);
+                }
+            }
+            Target::Encoder(encoder) => {
+                let mut text = text;
+                loop {
+                    let (result, read) = encoder.encode_from_utf8_to_vec_without_replacement(
+                        text,
+                        &mut self.out,
+                        last,
+                    );
+                    text = &text[read..];
+                    match result {
+                        EncoderResult::InputEmpty => break,
+                        EncoderResult::OutputFull => self.out.reserve(
+                            encoder
+                                .max_buffer_length_from_utf8_without_replacement(text.len())
+                                .unwrap_or(text.len())
+                                .max(16),
+                        ),
+                        EncoderResult::Unmappable(c) => {
+                            return Err(io::Error::new(
+                                io::ErrorKind::InvalidData,
+                                format!("{c:?} cannot be encoded in {}", encoder.encoding().name()),
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        self.inner.write_all(&self.out)
+    }
+}
+
+impl<W: Write> Write for SinkWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &mut self.restorer {
+            Some(restorer) => restorer.restore(buf, &mut self.pending, false),
+            None if matches!(self.target, Target::Utf8) => return self.inner.write(buf),
+            None => self.pending.extend_from_slice(buf),
+        }
+        self.write_pending()?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> std::fmt::Debug for SinkWriter<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let encoding = match &self.target {
+            Target::Utf8 => "UTF-8",
+            Target::Utf16 { big_endian: false } => "UTF-16LE",
+            Target::Utf16 { big_endian: true } => "UTF-16BE",
+            Target::Encoder(encoder) => encoder.encoding().name(),
+        };
+        f.debug_struct("SinkWriter")
+            .field("encoding", &encoding)
+            .field("restoring", &self.restorer.is_some())
+            .field("pending", &self.pending.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_rows(dialect: &Dialect, rows: &[&[&str]]) -> Vec<u8> {
+        let mut writer = dialect.writer_builder().from_writer(Vec::new());
+        for row in rows {
+            writer.write_record(*row).unwrap();
+        }
+        writer.into_inner().unwrap()
+    }
+
+    #[test]
+    fn test_writer_builder_dialect() {
+        let dialect = Dialect {
+            delimiter: b';',
+            quote: Quote::Some(b'\''),
+            line_terminator: LineTerminator::CRLF,
+            ..Dialect::default()
+        };
+        let out = write_rows(&dialect, &[&["a", "b;c"], &["it's", "2"]]);
+        assert_eq!(out, b"a;'b;c'\r\n'it''s';2\r\n");
+    }
+
+    #[test]
+    fn test_writer_builder_backslash_escape() {
+        let dialect = Dialect {
+            escape: Some(b'\\'),
+            double_quote: false,
+            ..Dialect::default()
+        };
+        let out = write_rows(&dialect, &[&["say \"hi\"", "1"]]);
+        assert_eq!(out, b"\"say \\\"hi\\\"\",1\n");
+    }
+
+    #[test]
+    fn test_writer_builder_no_quote() {
+        let dialect = Dialect {
+            delimiter: b'\t',
+            quote: Quote::None,
+            line_terminator: LineTerminator::CR,
+            ..Dialect::default()
+        };
+        let out = write_rows(&dialect, &[&["a \"b\"", "c"]]);
+        assert_eq!(out, b"a \"b\"\tc\r");
+    }
+
+    #[test]
+    fn test_sink_writer_encodes() {
+        // A character split across writes
+        let mut sink = SinkWriter::new(Vec::new(), encoding_rs::WINDOWS_1252);
+        sink.write_all(b"Ren\xC3").unwrap();
+        sink.write_all(b"\xA9;1\n").unwrap();
+        assert_eq!(sink.into_inner().unwrap(), b"Ren\xE9;1\n");
+
+        let mut sink = SinkWriter::new(Vec::new(), encoding_rs::UTF_16LE);
+        sink.write_bom().unwrap();
+        sink.write_all("a,é\n".as_bytes()).unwrap();
+        assert_eq!(
+            sink.into_inner().unwrap(),
+            b"\xFF\xFEa\x00,\x00\xE9\x00\n\x00"
+        );
+
+        let mut sink = SinkWriter::new(Vec::new(), encoding_rs::WINDOWS_1252);
+        let err = sink.write_all("€→".as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
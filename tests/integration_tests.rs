@@ -1,6 +1,9 @@
 //! Integration tests for csv-nose
 
-use csv_nose::{DatePreference, Quote, SampleSize, Sniffer, Type};
+use csv_nose::{
+    CellError, Date, DatePreference, LineTerminator, Metadata, Quote, SampleSize, Separator,
+    Sniffer, Type, TypedRecord, Value, encoding_rs,
+};
 use std::io::Cursor;
 use std::io::Write;
 use tempfile::NamedTempFile;
@@ -125,6 +128,49 @@ fn test_sniff_from_reader() {
     assert_eq!(metadata.num_fields, 3);
 }
 
+#[test]
+fn test_sniff_from_non_seekable_reader() {
+    // A pipe-like reader that returns at most 4 bytes per read
+    struct Trickle<'a>(&'a [u8]);
+    impl std::io::Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = buf.len().min(self.0.len()).min(4);
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    let data = b"id;name\n1;Alice\n2;Bob\n3;Carol\n";
+    let mut sniffer = Sniffer::new();
+    let metadata = sniffer.sniff_reader(Trickle(data)).unwrap();
+    assert_eq!(metadata.dialect.delimiter, b';');
+    assert_eq!(metadata.fields, vec!["id", "name"]);
+
+    // The sample stops after 16 bytes even though more input is available
+    sniffer.sample_size(SampleSize::Bytes(16));
+    let mut reader = Trickle(data);
+    let metadata = sniffer.sniff_reader(&mut reader).unwrap();
+    assert_eq!(metadata.dialect.delimiter, b';');
+    assert_eq!(reader.0.len(), data.len() - 17);
+}
+
+#[test]
+fn test_sniff_state_chunks() {
+    let data = b"name,amount\nAlice,10\nBob,20\nCarol,30\n";
+    let sniffer = Sniffer::new();
+
+    let mut state = sniffer.sniff_state();
+    for chunk in data.chunks(5) {
+        assert!(state.feed(chunk));
+    }
+    let metadata = state.finish().unwrap();
+
+    assert_eq!(metadata.dialect.delimiter, b',');
+    assert_eq!(metadata.fields, vec!["name", "amount"]);
+    assert_eq!(metadata.types, vec![Type::Text, Type::Unsigned]);
+}
+
 #[test]
 fn test_sniff_from_file() {
     let mut temp_file = NamedTempFile::new().unwrap();
@@ -198,6 +244,37 @@ fn test_utf8_bom() {
 
     assert_eq!(metadata.dialect.delimiter, b',');
     assert!(metadata.dialect.is_utf8);
+    assert!(metadata.encoding.has_bom);
+    assert_eq!(metadata.encoding.name(), "UTF-8");
+}
+
+#[test]
+fn test_encoding_reported() {
+    // "город;население" header and Cyrillic city names in Windows-1251
+    let (encoded, _, _) = encoding_rs::WINDOWS_1251.encode(
+        "город;население;страна
+Москва;12600000;Россия
+Казань;1250000;Россия
+         Самара;1150000;Россия
+Пермь;1040000;Россия
+",
+    );
+    let metadata = Sniffer::new().sniff_bytes(&encoded).unwrap();
+
+    assert_eq!(metadata.dialect.delimiter, b';');
+    assert_eq!(metadata.encoding.name(), "windows-1251");
+    assert!(!metadata.encoding.is_utf8);
+    assert!(!metadata.encoding.has_bom);
+    assert!(!metadata.encoding.had_errors);
+
+    let utf16: Vec<u8> = [0xFF, 0xFE]
+        .into_iter()
+        .chain("a,b\n1,2\n3,4\n".encode_utf16().flat_map(u16::to_le_bytes))
+        .collect();
+    let metadata = Sniffer::new().sniff_bytes(&utf16).unwrap();
+    assert_eq!(metadata.encoding.encoding, encoding_rs::UTF_16LE);
+    assert!(metadata.encoding.has_bom);
+    assert_eq!(metadata.dialect.delimiter, b',');
 }
 
 #[test]
@@ -269,6 +346,406 @@ fn test_builder_chaining() {
     let _ = sniffer_ref.sniff_bytes(data);
 }
 
+#[test]
+fn test_forced_header_and_preamble() {
+    let data = b"Exported by FeedTool\nGenerated 2024-03-01 10:00\n2019,2020\n15,22\n31,40\n";
+
+    let mut sniffer = Sniffer::new();
+    sniffer.preamble_rows(2).header(true);
+    let metadata = sniffer.sniff_bytes(data).unwrap();
+
+    assert_eq!(metadata.dialect.delimiter, b',');
+    assert_eq!(metadata.dialect.header.num_preamble_rows, 2);
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(metadata.fields, vec!["2019", "2020"]);
+    assert_eq!(metadata.types, vec![Type::Unsigned, Type::Unsigned]);
+}
+
+#[test]
+fn test_sniff_and_open_reader() {
+    let mut temp_file = NamedTempFile::new().unwrap();
+    // windows-1252 with a comment preamble
+    temp_file
+        .write_all(b"# export\n# v2\nnom;ville\nRen\xE9;Orl\xE9ans\nZo\xE9;Nice\nL\xE9a;Caen\n")
+        .unwrap();
+    temp_file.flush().unwrap();
+
+    let mut sniffer = Sniffer::new();
+    let (metadata, mut reader) = sniffer.sniff_and_open(temp_file.path()).unwrap();
+    assert_eq!(metadata.encoding.name(), "windows-1252");
+    assert_eq!(metadata.dialect.header.num_preamble_rows, 2);
+
+    assert_eq!(reader.headers().unwrap(), vec!["nom", "ville"]);
+    let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0], vec!["René", "Orléans"]);
+}
+
+#[test]
+fn test_reader_from_stacked_header() {
+    let data = b"Region,Revenue,,Cost,\nName,2023,2024,2023,2024\nNorth,10.5,11.2,4.1,4.3\nSouth,9.8,10.1,3.9,4.0\nEast,7.2,7.9,2.8,3.1\nWest,8.4,8.8,3.3,3.5\n";
+    let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+    assert_eq!(metadata.dialect.header.num_header_rows, 2);
+
+    let mut reader = metadata.reader_from(&data[..]).unwrap();
+    assert_eq!(
+        reader.headers().unwrap().iter().collect::<Vec<_>>(),
+        metadata.fields
+    );
+    let first = reader.records().next().unwrap().unwrap();
+    assert_eq!(&first[0], "North");
+}
+
+#[test]
+fn test_reader_keeps_rows_starting_with_slash() {
+    let data = b"// exported\npath,size\n/usr/bin,10\n/etc,20\n/var,30\n/opt,40\n";
+    let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+    assert_eq!(metadata.dialect.comment, None);
+    assert_eq!(metadata.dialect.header.num_preamble_rows, 1);
+
+    let mut reader = metadata.reader_from(&data[..]).unwrap();
+    assert_eq!(reader.headers().unwrap(), vec!["path", "size"]);
+    let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[0], vec!["/usr/bin", "10"]);
+}
+
+#[test]
+fn test_reader_keeps_rows_starting_with_hash() {
+    let data = b"id,name\n#1,Ann\n#2,Bo\n3,Cy\n4,Di\n5,Ed\n";
+    let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+    assert_eq!(metadata.dialect.comment, None);
+
+    let mut reader = metadata.reader_from(&data[..]).unwrap();
+    let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+    assert_eq!(rows.len(), 5);
+    assert_eq!(rows[0], vec!["#1", "Ann"]);
+}
+
+#[test]
+fn test_unicode_separator_latin1() {
+    // "§" is 0xA7 in windows-1252 and becomes 0xC2 0xA7 after transcoding
+    let data = b"# feed\nnom\xA7ville\xA7prix\nRen\xE9\xA7\"Orl\xE9ans \xA7 Loiret\"\xA71.5\nZo\xE9\xA7Nice\xA72.0\nL\xE9a\xA7Caen\xA73.25\nYo\xA7Metz\xA74.0\n";
+    let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+    assert_eq!(metadata.dialect.separator, Separator::Char('\u{a7}'));
+    assert_eq!(metadata.fields, vec!["nom", "ville", "prix"]);
+    assert_eq!(metadata.types[2], Type::Float);
+
+    let mut reader = metadata.reader_from(&data[..]).unwrap();
+    assert_eq!(reader.headers().unwrap(), vec!["nom", "ville", "prix"]);
+    let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[0], vec!["René", "Orléans § Loiret", "1.5"]);
+    round_trip(&metadata, data);
+}
+
+#[test]
+fn test_round_trip_unicode_separator() {
+    let data =
+        "id§name§city\n1§Ann§Paris\n2§Bo§\"Nice § Cannes\"\n3§Cy§Lyon\n4§Di§Metz\n".as_bytes();
+    let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+    assert_eq!(metadata.dialect.separator, Separator::Char('§'));
+    round_trip(&metadata, data);
+}
+
+#[test]
+fn test_multi_char_separator_reader() {
+    let data = b"id~|~name~|~note\r\n1~|~Ann~|~\"a ~|~ b\"\r\n2~|~Bo~|~plain\r\n3~|~Cy~|~x|y\r\n4~|~Di~|~z\r\n5~|~Ed~|~w\r\n6~|~Flo~|~v\r\n7~|~Gus~|~u\r\n8~|~Hal~|~t\r\n9~|~Ida~|~s\r\n";
+    let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+    assert_eq!(
+        metadata.dialect.separator,
+        Separator::Sequence(b"~|~".to_vec())
+    );
+    assert_eq!(metadata.num_fields, 3);
+
+    let mut reader = metadata.reader_from(&data[..]).unwrap();
+    assert_eq!(reader.headers().unwrap(), vec!["id", "name", "note"]);
+    let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+    assert_eq!(rows[0], vec!["1", "Ann", "a ~|~ b"]);
+    assert_eq!(rows[2], vec!["3", "Cy", "x|y"]);
+}
+
+#[test]
+fn test_round_trip_multi_char_separator() {
+    let data = b"id::name::qty\n1::Ann::3\n2::\"Bo :: Cy\"::5\n3::Di::7\n4::Ed::9\n5::Flo::2\n";
+    let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+    assert_eq!(
+        metadata.dialect.separator,
+        Separator::Sequence(b"::".to_vec())
+    );
+    round_trip(&metadata, data);
+}
+
+#[test]
+fn test_whitespace_run_reader() {
+    let data = b"  PID TTY          TIME CMD\n    1 ?        00:00:02 init\n  412 pts/0    00:00:00 bash\n 9876 pts/1    00:01:15 vim\n   77 ?        00:00:00 cron\n";
+    let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+    assert_eq!(metadata.dialect.separator, Separator::Whitespace);
+    assert!(!metadata.dialect.flexible);
+    assert_eq!(metadata.fields, vec!["PID", "TTY", "TIME", "CMD"]);
+
+    let mut reader = metadata.reader_from(&data[..]).unwrap();
+    let rows: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+    assert_eq!(rows.len(), 4);
+    assert_eq!(rows[2], vec!["9876", "pts/1", "00:01:15", "vim"]);
+}
+
+#[test]
+fn test_round_trip_keeps_dialect_and_preamble() {
+    let data = b"\xEF\xBB\xBF# source: ledger\r\n# version 3\r\nid;label\r\n1;'a;b'\r\n2;plain\r\n3;'it''s'\r\n";
+    let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+    assert_eq!(metadata.preamble, b"# source: ledger\r\n# version 3\r\n");
+
+    let mut reader = metadata.reader_from(&data[..]).unwrap();
+    let mut writer = metadata.writer_from(Vec::new(), true).unwrap();
+    writer.write_record(reader.headers().unwrap()).unwrap();
+    for record in reader.records() {
+        writer.write_record(&record.unwrap()).unwrap();
+    }
+    assert_eq!(writer.into_inner().unwrap().into_inner().unwrap(), data);
+}
+
+#[test]
+fn test_round_trip_non_utf8() {
+    // windows-1252 with a preamble line
+    let data = b"# caf\xE9 export\nnom;ville\nRen\xE9;Orl\xE9ans\nZo\xE9;N\xEEmes\nL\xE9a;Caen\n";
+    let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+    assert_eq!(metadata.encoding.name(), "windows-1252");
+    round_trip(&metadata, data);
+
+    // UTF-16LE with a BOM
+    let text = "id,name\n1,René\n2,Zoé\n3,Léa\n";
+    let mut data = b"\xFF\xFE".to_vec();
+    data.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+    let metadata = Sniffer::new().sniff_bytes(&data).unwrap();
+    assert!(metadata.encoding.has_bom);
+    round_trip(&metadata, &data);
+}
+
+fn round_trip(metadata: &Metadata, data: &[u8]) {
+    let mut reader = metadata.reader_from(data).unwrap();
+    let mut writer = metadata.writer_from(Vec::new(), true).unwrap();
+    writer.write_record(reader.headers().unwrap()).unwrap();
+    for record in reader.records() {
+        writer.write_record(&record.unwrap()).unwrap();
+    }
+    assert_eq!(writer.into_inner().unwrap().into_inner().unwrap(), data);
+}
+
+#[test]
+fn test_typed_reader_values_and_cell_errors() {
+    let sample = b"id,price,active,shipped\n1,\"1,250.50\",yes,2024-01-15\n2,99.5,no,2024-02-01\n3,12,yes,NULL\n";
+    let mut data = sample.to_vec();
+    data.extend_from_slice(b"x4,7.25,no,2024-03-10\n");
+
+    let metadata = Sniffer::new().sniff_bytes(sample).unwrap();
+    assert_eq!(
+        metadata.types,
+        vec![Type::Unsigned, Type::Float, Type::Boolean, Type::Date]
+    );
+
+    let rows: Vec<TypedRecord> = metadata
+        .typed_reader_from(&data[..])
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(rows.len(), 4);
+    assert_eq!(
+        rows[0],
+        vec![
+            Ok(Value::U64(1)),
+            Ok(Value::F64(1250.5)),
+            Ok(Value::Bool(true)),
+            Ok(Value::Date(Date::new(2024, 1, 15).unwrap())),
+        ]
+    );
+    assert_eq!(rows[2][3], Ok(Value::Null));
+    assert_eq!(
+        rows[3][0],
+        Err(CellError {
+            column: 0,
+            expected: Type::Unsigned,
+            value: "x4".to_string(),
+        })
+    );
+    assert_eq!(rows[3][1], Ok(Value::F64(7.25)));
+}
+
+#[test]
+fn test_typed_reader_uses_sniffer_date_preference() {
+    let data = b"id,due\n1,03/04/2024\n2,25/12/2024\n3,01/02/2024\n";
+    let mut sniffer = Sniffer::new();
+    sniffer.date_preference(DatePreference::DmyFormat);
+    let metadata = sniffer.sniff_bytes(data).unwrap();
+    assert_eq!(metadata.types[1], Type::Date);
+    assert_eq!(metadata.date_preference, DatePreference::DmyFormat);
+
+    let rows: Vec<TypedRecord> = metadata
+        .typed_reader_from(&data[..])
+        .unwrap()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(rows[0][1], Ok(Value::Date(Date::new(2024, 4, 3).unwrap())));
+    assert_eq!(
+        rows[1][1],
+        Ok(Value::Date(Date::new(2024, 12, 25).unwrap()))
+    );
+}
+
+#[test]
+fn test_candidate_delimiters_restrict_search() {
+    // Semicolons inside the values would win against the full built-in set
+    let data = b"a;b|c;d\n1;2|3;4\n5;6|7;8\n9;0|1;2\n";
+
+    let mut sniffer = Sniffer::new();
+    sniffer.candidate_delimiters(b"|\t");
+    let metadata = sniffer.sniff_bytes(data).unwrap();
+    assert_eq!(metadata.dialect.delimiter, b'|');
+    assert_eq!(metadata.fields, vec!["a;b", "c;d"]);
+}
+
+#[test]
+fn test_candidates_expose_runner_up() {
+    let data = b"a;b,c\n1;2,3\n4;5,6\n7;8,9\n";
+    let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+
+    let winner = &metadata.candidates[0];
+    let runner_up = &metadata.candidates[1];
+    assert_eq!(winner.delimiter, metadata.dialect.delimiter);
+    assert!(runner_up.gamma > 0.0);
+    assert!(winner.tau_0 > 0.0 && winner.tau_1 > 0.0);
+    assert_eq!(winner.num_rows, 4);
+}
+
+#[test]
+fn test_confidence_reflects_ambiguity() {
+    let clear = Sniffer::new()
+        .sniff_bytes(b"id,name,price\n1,apple,1.50\n2,pear,2.25\n3,plum,0.75\n4,fig,3.10\n")
+        .unwrap();
+    let ambiguous = Sniffer::new()
+        .sniff_bytes(b"word\nalpha\nbeta\ngamma\n")
+        .unwrap();
+
+    assert!(clear.confidence > ambiguous.confidence);
+    assert!((0.0..=1.0).contains(&clear.confidence));
+    assert!((0.0..=1.0).contains(&ambiguous.confidence));
+}
+
+#[test]
+fn test_backslash_escaped_quotes() {
+    // MySQL-style export: embedded quotes escaped with a backslash
+    let data = b"id,name,comment\n\
+1,\"Alice\",\"She said \\\"hello, world\\\"\"\n\
+2,\"Bob\",\"plain text\"\n\
+3,\"Carol\",\"a \\\"quoted, value\\\" here\"\n\
+4,\"Dan\",\"more text\"\n";
+    let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+
+    assert_eq!(metadata.dialect.delimiter, b',');
+    assert_eq!(metadata.dialect.quote, Quote::Some(b'"'));
+    assert_eq!(metadata.dialect.escape, Some(b'\\'));
+    assert!(!metadata.dialect.double_quote);
+    assert_eq!(metadata.num_fields, 3);
+}
+
+#[test]
+fn test_doubled_quotes_escape() {
+    let data = b"id,comment\n1,\"She said \"\"hi, there\"\"\"\n2,\"plain\"\n3,\"x \"\"y\"\"\"\n";
+    let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+
+    assert_eq!(metadata.dialect.escape, None);
+    assert!(metadata.dialect.double_quote);
+    assert_eq!(metadata.num_fields, 2);
+}
+
+#[test]
+fn test_line_terminator_reported() {
+    let crlf = Sniffer::new()
+        .sniff_bytes(b"a,b\r\n1,\"x\ny\"\r\n2,z\r\n")
+        .unwrap();
+    assert_eq!(crlf.dialect.line_terminator, LineTerminator::CRLF);
+
+    let lf = Sniffer::new().sniff_bytes(b"a,b\n1,2\n3,4\n").unwrap();
+    assert_eq!(lf.dialect.line_terminator, LineTerminator::LF);
+
+    let mixed = Sniffer::new().sniff_bytes(b"a,b\r\n1,2\n3,4\r\n").unwrap();
+    assert_eq!(mixed.dialect.line_terminator, LineTerminator::Mixed);
+    assert_eq!(mixed.dialect.line_terminator.as_str(), "mixed");
+}
+
+#[test]
+fn test_footer_rows_detected() {
+    let data = b"region,units,revenue\n\
+North,12,1500.50\n\
+South,8,980.25\n\
+East,15,2100.00\n\
+West,9,1120.75\n\
+Total,44,5701.50\n\
+,,\n\
+Generated by ReportServer on 2024-03-01\n";
+    let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+
+    assert_eq!(metadata.dialect.delimiter, b',');
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(metadata.dialect.header.num_footer_rows, 3);
+    assert_eq!(
+        metadata.types,
+        vec![Type::Text, Type::Unsigned, Type::Float]
+    );
+
+    let clean = Sniffer::new().sniff_bytes(b"a,b\n1,2\n3,4\n5,6\n").unwrap();
+    assert_eq!(clean.dialect.header.num_footer_rows, 0);
+}
+
+#[test]
+fn test_multi_row_header() {
+    let data = b",Population,,Area\n\
+Country,Urban,Rural,Land\n\
+,thousands,thousands,km2\n\
+France,52000,15000,547557\n\
+Spain,38000,9000,498980\n\
+Italy,42000,17000,294140\n";
+    let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+
+    assert!(metadata.dialect.header.has_header_row);
+    assert_eq!(metadata.dialect.header.num_header_rows, 3);
+    assert_eq!(
+        metadata.fields,
+        vec![
+            "Country",
+            "Population_Urban_thousands",
+            "Population_Rural_thousands",
+            "Area_Land_km2"
+        ]
+    );
+    assert_eq!(
+        metadata.types,
+        vec![Type::Text, Type::Unsigned, Type::Unsigned, Type::Unsigned]
+    );
+
+    let single = Sniffer::new()
+        .sniff_bytes(b"name,age\nAlice,30\nBob,25\nCarol,41\n")
+        .unwrap();
+    assert_eq!(single.dialect.header.num_header_rows, 1);
+}
+
+#[test]
+fn test_all_text_header() {
+    let with_header = Sniffer::new()
+        .sniff_bytes(
+            "Nom;Prénom;Ville\nDupont;Jean;Paris\nMartin;Claire;Lyon\nBernard;Luc;Nantes\n"
+                .as_bytes(),
+        )
+        .unwrap();
+    assert!(with_header.dialect.header.has_header_row);
+    assert_eq!(with_header.fields, vec!["Nom", "Prénom", "Ville"]);
+
+    let without_header = Sniffer::new()
+        .sniff_bytes(b"Alice,Paris\nBob,Lyon\nCarol,Rome\n")
+        .unwrap();
+    assert!(!without_header.dialect.header.has_header_row);
+}
+
 #[test]
 fn test_many_columns() {
     // Generate CSV with many columns
@@ -356,3 +833,24 @@ fn test_avg_record_len_regression_nyc_311() {
         metadata.avg_record_len
     );
 }
+
+#[test]
+fn test_reader_skips_preamble_with_blank_line() {
+    let data = b"Sales Report\n\nGenerated 2024\na,b,c\n1,2,3\n4,5,6\n7,8,9\n10,11,12\n";
+    let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+    assert_eq!(metadata.dialect.header.num_preamble_rows, 2);
+    assert_eq!(metadata.dialect.header.num_preamble_lines, 3);
+
+    let mut reader = metadata.reader_from(&data[..]).unwrap();
+    assert_eq!(reader.headers().unwrap(), vec!["a", "b", "c"]);
+    assert_eq!(reader.records().count(), 4);
+}
+
+#[test]
+fn test_round_trip_preamble_with_blank_line() {
+    let data =
+        b"Sales Report\r\n\r\nGenerated 2024\r\na,b,c\r\n1,2,3\r\n4,5,6\r\n7,8,9\r\n10,11,12\r\n";
+    let metadata = Sniffer::new().sniff_bytes(data).unwrap();
+    assert_eq!(metadata.preamble, b"Sales Report\r\n\r\nGenerated 2024\r\n");
+    round_trip(&metadata, data);
+}